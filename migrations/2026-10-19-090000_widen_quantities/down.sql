@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE user_items ALTER COLUMN quantity TYPE SMALLINT;
+ALTER TABLE user_ingredients ALTER COLUMN quantity TYPE SMALLINT;
+ALTER TABLE recipes ALTER COLUMN quantity TYPE SMALLINT;
\ No newline at end of file
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+ALTER TABLE user_items ALTER COLUMN quantity TYPE INTEGER;
+ALTER TABLE user_ingredients ALTER COLUMN quantity TYPE INTEGER;
+ALTER TABLE recipes ALTER COLUMN quantity TYPE INTEGER;
\ No newline at end of file
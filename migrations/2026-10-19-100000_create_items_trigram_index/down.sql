@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS items_unaccent_name_trgm_index;
\ No newline at end of file
@@ -0,0 +1,9 @@
+-- Your SQL goes here
+-- pg_trgm is created as superuser like unaccent (see SETUP.md), without it the search is done in process
+DO $$
+BEGIN
+  IF EXISTS (SELECT 1 FROM pg_extension WHERE extname = 'pg_trgm') THEN
+    CREATE INDEX items_unaccent_name_trgm_index ON items USING gin (public.f_unaccent(name) gin_trgm_ops);
+  END IF;
+END
+$$;
\ No newline at end of file
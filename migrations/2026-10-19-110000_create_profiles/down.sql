@@ -0,0 +1,12 @@
+-- This file should undo anything in `up.sql`
+DELETE FROM user_items WHERE profile_id <> (SELECT MIN(id) FROM profiles);
+ALTER TABLE user_items DROP CONSTRAINT user_items_pkey;
+ALTER TABLE user_items DROP COLUMN profile_id;
+ALTER TABLE user_items ADD PRIMARY KEY (item_id);
+
+DELETE FROM user_ingredients WHERE profile_id <> (SELECT MIN(id) FROM profiles);
+ALTER TABLE user_ingredients DROP CONSTRAINT user_ingredients_pkey;
+ALTER TABLE user_ingredients DROP COLUMN profile_id;
+ALTER TABLE user_ingredients ADD PRIMARY KEY (item_id);
+
+DROP TABLE profiles;
\ No newline at end of file
@@ -0,0 +1,20 @@
+-- Your SQL goes here
+CREATE TABLE profiles (
+  id SERIAL PRIMARY KEY,
+  name VARCHAR NOT NULL UNIQUE
+);
+
+-- The wish list and inventory saved before profiles go to the default one
+INSERT INTO profiles (name) VALUES ('Default');
+
+ALTER TABLE user_items ADD COLUMN profile_id INTEGER REFERENCES profiles(id) ON DELETE CASCADE;
+UPDATE user_items SET profile_id = (SELECT id FROM profiles WHERE name = 'Default');
+ALTER TABLE user_items ALTER COLUMN profile_id SET NOT NULL;
+ALTER TABLE user_items DROP CONSTRAINT user_items_pkey;
+ALTER TABLE user_items ADD PRIMARY KEY (profile_id, item_id);
+
+ALTER TABLE user_ingredients ADD COLUMN profile_id INTEGER REFERENCES profiles(id) ON DELETE CASCADE;
+UPDATE user_ingredients SET profile_id = (SELECT id FROM profiles WHERE name = 'Default');
+ALTER TABLE user_ingredients ALTER COLUMN profile_id SET NOT NULL;
+ALTER TABLE user_ingredients DROP CONSTRAINT user_ingredients_pkey;
+ALTER TABLE user_ingredients ADD PRIMARY KEY (profile_id, item_id);
\ No newline at end of file
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE journal_entries;
+DROP SEQUENCE journal_batch_id_seq;
\ No newline at end of file
@@ -0,0 +1,17 @@
+-- Your SQL goes here
+-- Entries of the same action share a batch and are undone together
+CREATE SEQUENCE journal_batch_id_seq;
+
+CREATE TABLE journal_entries (
+  id SERIAL PRIMARY KEY,
+  profile_id INTEGER REFERENCES profiles(id) ON DELETE CASCADE NOT NULL,
+  batch_id BIGINT NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  item_id INTEGER REFERENCES items(id) NOT NULL,
+  target VARCHAR NOT NULL,
+  delta INTEGER NOT NULL,
+  reason VARCHAR NOT NULL,
+  undone BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX journal_entries_batch_index ON journal_entries (profile_id, batch_id);
\ No newline at end of file
@@ -0,0 +1,9 @@
+-- Your SQL goes here
+-- Price of one item when bought by lots of 1, 10 or 100, the latest date is used
+CREATE TABLE prices (
+  item_id INTEGER REFERENCES items(id) NOT NULL,
+  lot_size SMALLINT NOT NULL CHECK (lot_size IN (1, 10, 100)),
+  unit_price BIGINT NOT NULL CHECK (unit_price >= 0),
+  price_date DATE NOT NULL DEFAULT CURRENT_DATE,
+  PRIMARY KEY (item_id, lot_size, price_date)
+);
\ No newline at end of file
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE user_items DROP COLUMN project_id;
+
+DROP TABLE projects;
\ No newline at end of file
@@ -0,0 +1,13 @@
+-- Your SQL goes here
+CREATE TABLE projects (
+  id SERIAL PRIMARY KEY,
+  profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+  name VARCHAR NOT NULL,
+  -- Lower is done first, the inventory goes to the first projects
+  priority INTEGER NOT NULL,
+  deadline DATE,
+  UNIQUE (profile_id, name)
+);
+
+-- Wish list items without project stay in the main list
+ALTER TABLE user_items ADD COLUMN project_id INTEGER REFERENCES projects(id) ON DELETE SET NULL;
\ No newline at end of file
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE session_loot;
+
+DROP TABLE session_kills;
+
+DROP TABLE farming_sessions;
\ No newline at end of file
@@ -0,0 +1,23 @@
+-- Your SQL goes here
+CREATE TABLE farming_sessions (
+  id SERIAL PRIMARY KEY,
+  profile_id INTEGER REFERENCES profiles(id) ON DELETE CASCADE NOT NULL,
+  sub_area_id INTEGER REFERENCES sub_areas(id),
+  started_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  -- Sessions still running are not used for the drop rates
+  stopped_at TIMESTAMP
+);
+
+CREATE TABLE session_kills (
+  session_id INTEGER REFERENCES farming_sessions(id) ON DELETE CASCADE NOT NULL,
+  monster_id INTEGER REFERENCES monsters(id) NOT NULL,
+  kills INTEGER NOT NULL CHECK (kills >= 0),
+  PRIMARY KEY (session_id, monster_id)
+);
+
+CREATE TABLE session_loot (
+  session_id INTEGER REFERENCES farming_sessions(id) ON DELETE CASCADE NOT NULL,
+  item_id INTEGER REFERENCES items(id) NOT NULL,
+  quantity INTEGER NOT NULL CHECK (quantity >= 0),
+  PRIMARY KEY (session_id, item_id)
+);
\ No newline at end of file
@@ -0,0 +1,13 @@
+-- Your SQL goes here
+CREATE TABLE favorites (
+  id SERIAL PRIMARY KEY,
+  profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+  name VARCHAR NOT NULL,
+  -- Either a sub-area, or a map view: the point of the full map at the center and the scale
+  sub_area_id INTEGER REFERENCES sub_areas(id) ON DELETE CASCADE,
+  center_x REAL,
+  center_y REAL,
+  scale REAL,
+  UNIQUE (profile_id, name),
+  CHECK (sub_area_id IS NOT NULL OR (center_x IS NOT NULL AND center_y IS NOT NULL AND scale IS NOT NULL))
+);
\ No newline at end of file
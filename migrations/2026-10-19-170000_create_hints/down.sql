@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE hints;
+
+DROP TABLE hint_categories;
\ No newline at end of file
@@ -0,0 +1,14 @@
+-- Your SQL goes here
+CREATE TABLE hint_categories (
+  id INTEGER PRIMARY KEY,
+  name VARCHAR NOT NULL
+);
+
+-- Zaaps, workshops, banks... of the main world map
+CREATE TABLE hints (
+  id INTEGER PRIMARY KEY,
+  category_id INTEGER NOT NULL REFERENCES hint_categories(id) ON DELETE CASCADE,
+  name VARCHAR NOT NULL,
+  x SMALLINT NOT NULL,
+  y SMALLINT NOT NULL
+);
\ No newline at end of file
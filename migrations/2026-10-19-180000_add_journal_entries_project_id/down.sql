@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE journal_entries DROP COLUMN project_id;
\ No newline at end of file
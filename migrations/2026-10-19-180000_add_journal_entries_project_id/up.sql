@@ -0,0 +1,3 @@
+-- Your SQL goes here
+-- Project of a wish list item, given back when its removal is undone
+ALTER TABLE journal_entries ADD COLUMN project_id INTEGER REFERENCES projects(id) ON DELETE SET NULL;
\ No newline at end of file
@@ -0,0 +1,265 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap},
+};
+
+use diesel::PgConnection;
+
+use crate::database::models::{
+    item::{Item, ItemList, Quantity},
+    price::{Kamas, Price},
+};
+
+use super::{planner::CraftPlan, recipe_graph::RecipeGraph};
+
+pub const LOT_SIZES: [i16; 3] = [1, 10, 100];
+
+// Latest unit price of each item for each lot size
+#[derive(Default, Clone)]
+pub struct PriceList {
+    prices: HashMap<i32, BTreeMap<i16, Kamas>>,
+}
+
+impl PriceList {
+    pub fn load(connection: &mut PgConnection) -> Self {
+        use crate::database::schema::prices;
+        use diesel::prelude::*;
+
+        let prices = prices::table
+            .select(Price::as_select())
+            .order(prices::price_date)
+            .load(connection)
+            .unwrap();
+
+        Self::from_prices(prices)
+    }
+
+    // Prices must be sorted by date, later ones replace the older
+    pub fn from_prices(prices: Vec<Price>) -> Self {
+        let mut price_list = Self::default();
+
+        prices.into_iter().for_each(|price| {
+            price_list
+                .prices
+                .entry(price.item_id)
+                .or_default()
+                .insert(price.lot_size, price.unit_price);
+        });
+
+        price_list
+    }
+
+    pub fn unit_price(&self, item: &Item, lot_size: i16) -> Option<Kamas> {
+        self.prices
+            .get(&item.id)
+            .and_then(|lots| lots.get(&lot_size))
+            .copied()
+    }
+
+    // Cheapest mix of lots, buying more than needed is allowed when it costs less
+    pub fn buy_cost(&self, item: &Item, quantity: Quantity) -> Option<Kamas> {
+        if quantity <= 0 {
+            return Some(0);
+        }
+
+        let lots = self.prices.get(&item.id)?;
+        let lot_price =
+            |lot_size: i16, unit_price: Kamas| Kamas::from(lot_size).saturating_mul(unit_price);
+
+        // Lowest unit price, the biggest lot on a tie
+        let (&best_size, &best_unit_price) = lots
+            .iter()
+            .min_by_key(|(lot_size, unit_price)| (**unit_price, Reverse(**lot_size)))?;
+        let best_lot_price = lot_price(best_size, best_unit_price);
+
+        // Among best_size other lots, some always add up to a multiple of best_size and can be
+        // swapped for best lots without paying more, so fewer of them are enough
+        let max_size = lots.keys().max().copied().unwrap_or(1);
+        let others_max =
+            (usize::try_from(best_size).unwrap_or(1) - 1) * usize::try_from(max_size).unwrap_or(1);
+
+        // Cheapest cost of the other lots adding up to exactly each quantity
+        let mut others: Vec<Option<Kamas>> = vec![None; others_max + 1];
+        others[0] = Some(0);
+        (1..=others_max).for_each(|covered| {
+            others[covered] = lots
+                .iter()
+                .filter(|(lot_size, _)| **lot_size != best_size)
+                .filter_map(|(lot_size, unit_price)| {
+                    let smaller = covered.checked_sub(usize::try_from(*lot_size).ok()?)?;
+                    others[smaller]
+                        .map(|cost| cost.saturating_add(lot_price(*lot_size, *unit_price)))
+                })
+                .min();
+        });
+
+        let quantity = Kamas::from(quantity);
+        let best_size = Kamas::from(best_size);
+        others
+            .iter()
+            .enumerate()
+            .filter_map(|(covered, cost)| {
+                let rest = (quantity - covered as Kamas).max(0);
+                let best_lots = (rest + best_size - 1) / best_size;
+                Some(
+                    cost.as_ref()?
+                        .saturating_add(best_lots.saturating_mul(best_lot_price)),
+                )
+            })
+            .min()
+    }
+
+    // Total of the priced items and the items without a price
+    pub fn items_cost(&self, items: &ItemList) -> (Kamas, ItemList) {
+        let mut unpriced = ItemList::new();
+
+        let total = items
+            .iter()
+            .filter_map(|(item, quantity)| {
+                let cost = self.buy_cost(item, *quantity);
+                if cost.is_none() {
+                    unpriced.add_item(item, quantity);
+                }
+                cost
+            })
+            .fold(0, Kamas::saturating_add);
+
+        (total, unpriced)
+    }
+}
+
+pub struct CraftOrBuy {
+    pub item: Item,
+    pub quantity: Quantity,
+    // Buying every base ingredient of the full recipe, None when one has no price
+    pub craft_cost: Option<Kamas>,
+    pub buy_cost: Option<Kamas>,
+}
+
+impl CraftOrBuy {
+    pub fn craft_is_cheaper(&self) -> Option<bool> {
+        Some(self.craft_cost? <= self.buy_cost?)
+    }
+}
+
+#[derive(Default)]
+pub struct CraftCost {
+    // Cost of the base ingredients still missing
+    pub missing_cost: Kamas,
+    pub unpriced_missing: ItemList,
+    // One per item the plan crafts, same order as the plan tiers
+    pub craft_or_buy: Vec<CraftOrBuy>,
+}
+
+impl CraftCost {
+    pub fn new(recipe_graph: &RecipeGraph, craft_plan: &CraftPlan, prices: &PriceList) -> Self {
+        let (missing_cost, unpriced_missing) = prices.items_cost(&craft_plan.missing);
+
+        let craft_or_buy = craft_plan
+            .tiers
+            .iter()
+            .flat_map(|tier| tier.iter())
+            .map(|(item, quantity)| {
+                let (base_ingredients, _) = recipe_graph.get_full_recipe(item, quantity);
+                let (craft_cost, unpriced) = prices.items_cost(&base_ingredients);
+
+                CraftOrBuy {
+                    item: item.clone(),
+                    quantity: *quantity,
+                    craft_cost: unpriced.is_empty().then_some(craft_cost),
+                    buy_cost: prices.buy_cost(item, *quantity),
+                }
+            })
+            .collect();
+
+        Self {
+            missing_cost,
+            unpriced_missing,
+            craft_or_buy,
+        }
+    }
+}
+
+// 1234567 -> "1 234 567 k"
+pub fn format_kamas(kamas: Kamas) -> String {
+    let digits = kamas.unsigned_abs().to_string();
+    let groups = digits
+        .as_bytes()
+        .rchunks(3)
+        .rev()
+        .map(|group| String::from_utf8_lossy(group).into_owned())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if kamas < 0 {
+        format!("-{groups} k")
+    } else {
+        format!("{groups} k")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{format_kamas, PriceList};
+    use crate::{database::models::price::Price, test_database::bakery::bakery};
+
+    fn prices(lots: &[(i16, i64)]) -> PriceList {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+
+        PriceList::from_prices(
+            lots.iter()
+                .map(|(lot_size, unit_price)| Price::new(1, *lot_size, *unit_price, date))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn buys_the_cheapest_mix_of_lots() {
+        let wheat = bakery().wheat;
+        let price_list = prices(&[(1, 10), (10, 6), (100, 5)]);
+
+        // 2 lots of 10 and 3 alone
+        assert_eq!(price_list.buy_cost(&wheat, 23), Some(150));
+        // A lot of 10 costs less than 9 alone
+        assert_eq!(price_list.buy_cost(&wheat, 9), Some(60));
+        // A lot of 100 costs less than 9 lots of 10 and 5 alone
+        assert_eq!(price_list.buy_cost(&wheat, 95), Some(500));
+        assert_eq!(price_list.buy_cost(&wheat, 0), Some(0));
+    }
+
+    #[test]
+    fn smaller_lots_are_kept_when_bigger_ones_cost_more_per_unit() {
+        let wheat = bakery().wheat;
+        let price_list = prices(&[(1, 5), (10, 8)]);
+
+        // 20 alone rather than 2 lots of 10
+        assert_eq!(price_list.buy_cost(&wheat, 20), Some(100));
+
+        // Lots of 10 are the cheapest per unit, the lot of 100 is never bought
+        let price_list = prices(&[(1, 10), (10, 8), (100, 9)]);
+        assert_eq!(price_list.buy_cost(&wheat, 95), Some(770));
+        assert_eq!(price_list.buy_cost(&wheat, 99), Some(800));
+        assert_eq!(price_list.buy_cost(&wheat, 3), Some(30));
+    }
+
+    #[test]
+    fn missing_lot_sizes_are_skipped() {
+        let (wheat, water) = {
+            let bakery = bakery();
+            (bakery.wheat, bakery.water)
+        };
+        let price_list = prices(&[(100, 2)]);
+
+        assert_eq!(price_list.buy_cost(&wheat, 150), Some(400));
+        assert_eq!(price_list.buy_cost(&water, 1), None);
+    }
+
+    #[test]
+    fn kamas_are_grouped_by_thousands() {
+        assert_eq!(format_kamas(1234567), "1 234 567 k");
+        assert_eq!(format_kamas(950), "950 k");
+        assert_eq!(format_kamas(-1000), "-1 000 k");
+    }
+}
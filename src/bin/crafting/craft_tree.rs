@@ -0,0 +1,147 @@
+use std::cmp;
+
+use crate::database::models::item::{Item, ItemList, Quantity};
+
+use super::recipe_graph::RecipeGraph;
+
+// One item of a craft with what it needs, the inventory is shared between all the trees
+// in the order they are built so an item in stock is counted only once
+#[derive(Debug, PartialEq, Eq)]
+pub struct CraftTreeNode {
+    pub item: Item,
+    pub needed: Quantity,
+    pub from_stock: Quantity,
+    // Ingredients of what is not taken from the inventory
+    pub children: Vec<CraftTreeNode>,
+}
+
+impl CraftTreeNode {
+    // Wish list items are always crafted, like in the craft plan
+    pub fn for_wish_list(
+        recipe_graph: &RecipeGraph,
+        wish_list: &ItemList,
+        inventory: &ItemList,
+    ) -> Vec<Self> {
+        let mut inventory = inventory.clone();
+
+        wish_list
+            .iter()
+            .map(|(item, quantity)| {
+                if recipe_graph.is_craftable(item) {
+                    Self {
+                        item: item.clone(),
+                        needed: *quantity,
+                        from_stock: 0,
+                        children: Self::children(recipe_graph, item, *quantity, &mut inventory),
+                    }
+                } else {
+                    Self::new(recipe_graph, item, *quantity, &mut inventory)
+                }
+            })
+            .collect()
+    }
+
+    fn new(
+        recipe_graph: &RecipeGraph,
+        item: &Item,
+        needed: Quantity,
+        inventory: &mut ItemList,
+    ) -> Self {
+        let from_stock = inventory
+            .get_mut(item)
+            .map(|in_stock| {
+                let taken = cmp::min(*in_stock, needed);
+                *in_stock -= taken;
+                taken
+            })
+            .unwrap_or(0);
+
+        let children = if recipe_graph.is_craftable(item) {
+            Self::children(recipe_graph, item, needed - from_stock, inventory)
+        } else {
+            Vec::new()
+        };
+
+        Self {
+            item: item.clone(),
+            needed,
+            from_stock,
+            children,
+        }
+    }
+
+    fn children(
+        recipe_graph: &RecipeGraph,
+        item: &Item,
+        to_craft: Quantity,
+        inventory: &mut ItemList,
+    ) -> Vec<Self> {
+        if to_craft == 0 {
+            return Vec::new();
+        }
+
+        recipe_graph
+            .get_recipe(item, &to_craft)
+            .iter()
+            .map(|(ingredient, quantity)| Self::new(recipe_graph, ingredient, *quantity, inventory))
+            .collect()
+    }
+
+    // False when this item or one of its ingredients is missing
+    pub fn is_complete(&self) -> bool {
+        self.from_stock >= self.needed
+            || (!self.children.is_empty() && self.children.iter().all(Self::is_complete))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CraftTreeNode;
+    use crate::test_database::bakery::{bakery, list, Bakery};
+
+    #[test]
+    fn stock_is_shared_between_branches() {
+        let Bakery {
+            graph,
+            wheat,
+            water,
+            flour,
+            bread,
+            ..
+        } = bakery();
+
+        let wish_list = list(&[(&bread, 1)]);
+        let inventory = list(&[(&flour, 1), (&wheat, 2), (&water, 1)]);
+
+        let trees = CraftTreeNode::for_wish_list(&graph, &wish_list, &inventory);
+
+        assert_eq!(
+            trees,
+            vec![CraftTreeNode {
+                item: bread,
+                needed: 1,
+                from_stock: 0,
+                children: vec![
+                    CraftTreeNode {
+                        item: water,
+                        needed: 1,
+                        from_stock: 1,
+                        children: Vec::new(),
+                    },
+                    CraftTreeNode {
+                        item: flour,
+                        needed: 2,
+                        from_stock: 1,
+                        children: vec![CraftTreeNode {
+                            item: wheat,
+                            needed: 3,
+                            from_stock: 2,
+                            children: Vec::new(),
+                        }],
+                    },
+                ],
+            }]
+        );
+        assert!(!trees[0].is_complete());
+    }
+}
@@ -0,0 +1,5 @@
+pub mod cost;
+pub mod craft_tree;
+pub mod planner;
+pub mod project_progress;
+pub mod recipe_graph;
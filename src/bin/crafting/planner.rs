@@ -0,0 +1,233 @@
+use std::{cmp::Reverse, collections::BTreeMap};
+
+use crate::database::models::item::{Item, ItemList, Quantity};
+
+use super::recipe_graph::RecipeGraph;
+
+// What to do to get the wish list from the current inventory
+#[derive(Default, Debug, PartialEq, Eq)]
+pub struct CraftPlan {
+    // Items to craft, first tier is the closest to the base ingredients
+    pub tiers: Vec<ItemList>,
+    // Crafted items already in the inventory, used instead of crafting them
+    pub intermediates_from_stock: ItemList,
+    // Base ingredients taken from the inventory
+    pub base_from_stock: ItemList,
+    // Base ingredients still to farm or buy
+    pub missing: ItemList,
+}
+
+#[derive(Default)]
+struct Demand {
+    // Wish list quantity, always crafted when it can be
+    wanted: Quantity,
+    // Needed as an ingredient, taken from the inventory first
+    needed: Quantity,
+}
+
+impl CraftPlan {
+    // An item is only looked at once every item needing it is done, so its total demand is known.
+    // Items needing another one are always deeper so going from the deepest works
+    pub fn new(recipe_graph: &RecipeGraph, wish_list: &ItemList, inventory: &ItemList) -> Self {
+        let mut demands: BTreeMap<(Reverse<usize>, Item), Demand> = BTreeMap::new();
+        let mut tiers: BTreeMap<usize, ItemList> = BTreeMap::new();
+        let mut plan = Self::default();
+
+        wish_list.iter().for_each(|(item, quantity)| {
+            let demand = demands
+                .entry((Reverse(recipe_graph.depth(item)), item.clone()))
+                .or_default();
+
+            if recipe_graph.is_craftable(item) {
+                demand.wanted = demand.wanted.saturating_add(*quantity);
+            } else {
+                demand.needed = demand.needed.saturating_add(*quantity);
+            }
+        });
+
+        while let Some(((Reverse(depth), item), demand)) = demands.pop_first() {
+            let in_stock = *inventory.get(&item).unwrap_or(&0);
+            let from_stock = demand.needed.min(in_stock);
+            let to_make = demand
+                .wanted
+                .saturating_add(demand.needed.saturating_sub(from_stock));
+
+            if recipe_graph.is_craftable(&item) {
+                plan.intermediates_from_stock.add_item(&item, &from_stock);
+
+                if to_make > 0 {
+                    tiers.entry(depth).or_default().add_item(&item, &to_make);
+
+                    recipe_graph.get_recipe(&item, &to_make).iter().for_each(
+                        |(ingredient, quantity)| {
+                            let demand = demands
+                                .entry((
+                                    Reverse(recipe_graph.depth(ingredient)),
+                                    ingredient.clone(),
+                                ))
+                                .or_default();
+                            demand.needed = demand.needed.saturating_add(*quantity);
+                        },
+                    );
+                }
+            } else {
+                plan.base_from_stock.add_item(&item, &from_stock);
+                plan.missing.add_item(&item, &to_make);
+            }
+        }
+
+        plan.tiers = tiers.into_values().collect();
+        plan.intermediates_from_stock
+            .retain(|_, quantity| *quantity > 0);
+        plan.base_from_stock.retain(|_, quantity| *quantity > 0);
+        plan.missing.retain(|_, quantity| *quantity > 0);
+
+        plan
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CraftPlan;
+    use crate::{
+        database::models::item::{Item, ItemList},
+        test_database::bakery::{bakery, graph, list, Bakery},
+    };
+
+    #[test]
+    fn empty_inventory_crafts_everything() {
+        let Bakery {
+            graph,
+            wheat,
+            water,
+            flour,
+            bread,
+            cake,
+        } = bakery();
+
+        let plan = CraftPlan::new(&graph, &list(&[(&cake, 2)]), &ItemList::new());
+
+        assert_eq!(
+            plan,
+            CraftPlan {
+                tiers: vec![
+                    list(&[(&flour, 6)]),
+                    list(&[(&bread, 2)]),
+                    list(&[(&cake, 2)]),
+                ],
+                intermediates_from_stock: ItemList::new(),
+                base_from_stock: ItemList::new(),
+                missing: list(&[(&wheat, 18), (&water, 2)]),
+            }
+        );
+    }
+
+    #[test]
+    fn intermediates_in_stock_are_used_first() {
+        let Bakery {
+            graph,
+            wheat,
+            water,
+            flour,
+            bread,
+            cake,
+        } = bakery();
+
+        // The Pain covers one Gâteau, the Farine is shared by both branches
+        let inventory = list(&[(&bread, 1), (&flour, 2), (&wheat, 4)]);
+        let plan = CraftPlan::new(&graph, &list(&[(&cake, 2)]), &inventory);
+
+        assert_eq!(
+            plan,
+            CraftPlan {
+                tiers: vec![
+                    list(&[(&flour, 2)]),
+                    list(&[(&bread, 1)]),
+                    list(&[(&cake, 2)]),
+                ],
+                intermediates_from_stock: list(&[(&bread, 1), (&flour, 2)]),
+                base_from_stock: list(&[(&wheat, 4)]),
+                missing: list(&[(&wheat, 2), (&water, 1)]),
+            }
+        );
+    }
+
+    #[test]
+    fn wish_list_items_are_crafted_even_in_stock() {
+        let Bakery {
+            graph,
+            wheat,
+            water,
+            flour,
+            bread,
+            cake,
+        } = bakery();
+
+        // The Pain in stock goes to the Gâteau, the wanted Pain is crafted
+        let wish_list = list(&[(&bread, 1), (&cake, 1)]);
+        let inventory = list(&[(&bread, 1)]);
+        let plan = CraftPlan::new(&graph, &wish_list, &inventory);
+
+        assert_eq!(
+            plan,
+            CraftPlan {
+                tiers: vec![
+                    list(&[(&flour, 3)]),
+                    list(&[(&bread, 1)]),
+                    list(&[(&cake, 1)]),
+                ],
+                intermediates_from_stock: list(&[(&bread, 1)]),
+                base_from_stock: ItemList::new(),
+                missing: list(&[(&wheat, 9), (&water, 1)]),
+            }
+        );
+    }
+
+    #[test]
+    fn base_ingredients_on_wish_list_come_from_stock() {
+        let Bakery { graph, wheat, .. } = bakery();
+
+        let plan = CraftPlan::new(&graph, &list(&[(&wheat, 5)]), &list(&[(&wheat, 3)]));
+
+        assert!(plan.tiers.is_empty());
+        assert_eq!(plan.base_from_stock, list(&[(&wheat, 3)]));
+        assert_eq!(plan.missing, list(&[(&wheat, 2)]));
+    }
+
+    #[test]
+    fn cyclic_items_are_not_expanded() {
+        let stone = Item::new(7, "Pierre d'âme".to_owned(), 0, 7);
+        let fragment = Item::new(8, "Fragment d'âme".to_owned(), 0, 8);
+        let ring = Item::new(20, "Anneau".to_owned(), 1, 20);
+
+        let graph = graph(&[
+            (&stone, &fragment, 1),
+            (&fragment, &stone, 2),
+            (&ring, &stone, 1),
+        ]);
+        let plan = CraftPlan::new(&graph, &list(&[(&ring, 2)]), &ItemList::new());
+
+        assert_eq!(plan.tiers, vec![list(&[(&ring, 2)])]);
+        assert_eq!(plan.missing, list(&[(&stone, 2)]));
+    }
+
+    #[test]
+    fn same_input_gives_same_plan() {
+        let Bakery {
+            graph,
+            wheat,
+            flour,
+            bread,
+            cake,
+            ..
+        } = bakery();
+
+        let wish_list = list(&[(&cake, 3), (&bread, 2)]);
+        let inventory = list(&[(&flour, 5), (&wheat, 1)]);
+
+        assert_eq!(
+            CraftPlan::new(&graph, &wish_list, &inventory),
+            CraftPlan::new(&graph, &wish_list, &inventory)
+        );
+    }
+}
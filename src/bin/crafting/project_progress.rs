@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+
+use crate::database::models::item::{ItemList, Quantity};
+
+use super::{planner::CraftPlan, recipe_graph::RecipeGraph};
+
+// Share of the base ingredients each project already has, between 0 and 1. Projects are given
+// in priority order and take what they need from the inventory before the next ones
+pub fn projects_progress(
+    recipe_graph: &RecipeGraph,
+    projects: &[(i32, ItemList)],
+    inventory: &ItemList,
+) -> HashMap<i32, f32> {
+    let mut inventory = inventory.clone();
+
+    projects
+        .iter()
+        .map(|(project_id, wish_list)| {
+            let craft_plan = CraftPlan::new(recipe_graph, wish_list, &inventory);
+
+            craft_plan
+                .intermediates_from_stock
+                .iter()
+                .chain(craft_plan.base_from_stock.iter())
+                .for_each(|(item, quantity)| {
+                    if let Some(in_stock) = inventory.get_mut(item) {
+                        *in_stock -= quantity;
+                    }
+                });
+
+            let needed = wish_list
+                .iter()
+                .map(|(item, quantity)| {
+                    let (base_ingredients, _) = recipe_graph.get_full_recipe(item, quantity);
+                    base_ingredients
+                        .values()
+                        .copied()
+                        .fold(0, Quantity::saturating_add)
+                })
+                .fold(0, Quantity::saturating_add);
+            let missing = craft_plan
+                .missing
+                .values()
+                .copied()
+                .fold(0, Quantity::saturating_add);
+
+            let progress = if needed == 0 {
+                1f32
+            } else {
+                (1f32 - missing as f32 / needed as f32).clamp(0f32, 1f32)
+            };
+
+            (*project_id, progress)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::projects_progress;
+    use crate::{
+        database::models::item::ItemList,
+        test_database::bakery::{bakery, list, Bakery},
+    };
+
+    #[test]
+    fn first_projects_take_the_inventory() {
+        let Bakery {
+            graph,
+            wheat,
+            flour,
+            ..
+        } = bakery();
+
+        let projects = vec![
+            (1, list(&[(&flour, 1)])),
+            (2, list(&[(&flour, 2)])),
+            (3, ItemList::new()),
+        ];
+        let inventory = list(&[(&wheat, 6)]);
+
+        let progress = projects_progress(&graph, &projects, &inventory);
+
+        assert_eq!(progress[&1], 1f32);
+        assert_eq!(progress[&2], 0.5);
+        assert_eq!(progress[&3], 1f32);
+    }
+}
@@ -0,0 +1,458 @@
+use std::{
+    cmp,
+    collections::{HashMap, HashSet},
+};
+
+use diesel::PgConnection;
+use itertools::Itertools;
+use tracing::{trace_span, warn};
+
+use crate::database::models::{
+    item::{Item, ItemList, Quantity},
+    recipe::Recipe,
+};
+
+// All the recipes of the database, loaded once so recipe queries don't need a connection
+pub struct RecipeGraph {
+    items: HashMap<i32, Item>,
+    // result item id -> (ingredient item id, quantity)
+    ingredients: HashMap<i32, Vec<(i32, Quantity)>>,
+    // ingredient item id -> (result item id, quantity)
+    used_in: HashMap<i32, Vec<(i32, Quantity)>>,
+    // 0 for base ingredients, 1 + deepest ingredient for the others
+    depths: HashMap<i32, usize>,
+    cycles: Vec<Vec<Item>>,
+    cyclic_items: HashSet<i32>,
+}
+
+impl RecipeGraph {
+    pub fn load(connection: &mut PgConnection) -> Self {
+        use crate::database::schema::*;
+        use diesel::prelude::*;
+
+        let span = trace_span!("load recipe graph");
+        let _guard = span.enter();
+
+        let (items_result, items_ingredient) =
+            diesel::alias!(items as items_result, items as items_ingredient);
+
+        let recipes: Vec<(Item, Recipe, Item)> = items_result
+            .inner_join(
+                recipes::table.on(items_result.field(items::id).eq(recipes::result_item_id)),
+            )
+            .inner_join(
+                items_ingredient.on(items_ingredient
+                    .field(items::id)
+                    .eq(recipes::ingredient_item_id)),
+            )
+            .load(connection)
+            .unwrap();
+
+        let graph = Self::from_recipes(recipes);
+
+        graph.cycles.iter().for_each(|cycle| {
+            warn!(
+                "Recipe cycle found, its items will be treated as base ingredients: {}",
+                cycle.iter().map(|item| &item.name).join(" -> ")
+            );
+        });
+
+        graph
+    }
+
+    pub fn from_recipes(recipes: Vec<(Item, Recipe, Item)>) -> Self {
+        let mut items = HashMap::new();
+        let mut ingredients: HashMap<i32, Vec<(i32, Quantity)>> = HashMap::new();
+        let mut used_in: HashMap<i32, Vec<(i32, Quantity)>> = HashMap::new();
+
+        recipes
+            .into_iter()
+            .for_each(|(result_item, recipe, ingredient_item)| {
+                ingredients
+                    .entry(result_item.id)
+                    .or_default()
+                    .push((ingredient_item.id, recipe.quantity));
+                used_in
+                    .entry(ingredient_item.id)
+                    .or_default()
+                    .push((result_item.id, recipe.quantity));
+
+                items.insert(result_item.id, result_item);
+                items.insert(ingredient_item.id, ingredient_item);
+            });
+
+        // Keep the same order everytime the graph is loaded
+        ingredients.values_mut().for_each(|list| list.sort());
+        used_in.values_mut().for_each(|list| list.sort());
+
+        let mut graph = Self {
+            items,
+            ingredients,
+            used_in,
+            depths: HashMap::new(),
+            cycles: Vec::new(),
+            cyclic_items: HashSet::new(),
+        };
+
+        graph.find_cycles();
+        graph.compute_depths();
+
+        graph
+    }
+
+    pub fn has_recipe(&self, item: &Item) -> bool {
+        self.ingredients.contains_key(&item.id)
+    }
+
+    // Items in a cycle are never expanded, otherwise the expansion would never end
+    pub fn is_craftable(&self, item: &Item) -> bool {
+        self.has_recipe(item) && !self.cyclic_items.contains(&item.id)
+    }
+
+    pub fn get_recipe(&self, item: &Item, quantity: &Quantity) -> ItemList {
+        let mut items = ItemList::new();
+
+        if let Some(ingredients) = self.ingredients.get(&item.id) {
+            ingredients
+                .iter()
+                .for_each(|(ingredient_id, ingredient_quantity)| {
+                    items.add_item(
+                        &self.items[ingredient_id],
+                        &ingredient_quantity.saturating_mul(*quantity),
+                    );
+                });
+        }
+
+        items
+    }
+
+    pub fn get_full_recipe(&self, item: &Item, quantity: &Quantity) -> (ItemList, Vec<ItemList>) {
+        // used in loop to know which item we still need the recipe of
+        let mut items_to_make = ItemList::new();
+        items_to_make.add_item(item, quantity);
+
+        // The last materials of the items
+        let mut base_ingredients = ItemList::new();
+
+        // All the steps to create the item
+        let mut ingredients_steps: Vec<ItemList> = Vec::new();
+
+        // Temporary to set items to make
+        let mut next_items_to_make = ItemList::new();
+
+        while !items_to_make.is_empty() {
+            ingredients_steps.push(ItemList::new());
+            items_to_make.iter().for_each(|(item, quantity)| {
+                if self.is_craftable(item) {
+                    ingredients_steps
+                        .last_mut()
+                        .unwrap()
+                        .add_item(item, quantity);
+
+                    next_items_to_make.add_items(&self.get_recipe(item, quantity));
+                } else {
+                    base_ingredients.add_item(item, quantity);
+                }
+            });
+
+            items_to_make.clear();
+            items_to_make.add_items(&next_items_to_make);
+            next_items_to_make.clear();
+        }
+
+        // First is wanted item and last is empty
+        ingredients_steps.remove(0);
+        ingredients_steps.pop();
+
+        // put first the closest to base ingredients and last closest to final item
+        ingredients_steps.reverse();
+
+        (base_ingredients, ingredients_steps)
+    }
+
+    // Items whose recipe needs this item, with the quantity needed for one craft
+    pub fn used_in(&self, item: &Item) -> Vec<(&Item, Quantity)> {
+        self.used_in
+            .get(&item.id)
+            .map(|results| {
+                results
+                    .iter()
+                    .map(|(result_id, quantity)| (&self.items[result_id], *quantity))
+                    .sorted()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    // Times the recipe can be done with only what is in the inventory, ingredients are not crafted
+    pub fn max_craftable(&self, item: &Item, inventory: &ItemList) -> Quantity {
+        if !self.is_craftable(item) {
+            return 0;
+        }
+
+        self.ingredients[&item.id]
+            .iter()
+            .map(|(ingredient_id, quantity)| {
+                let in_stock = *inventory.get(&self.items[ingredient_id]).unwrap_or(&0);
+                cmp::max(in_stock, 0) / cmp::max(*quantity, 1)
+            })
+            .min()
+            .unwrap_or(0)
+    }
+
+    // Every recipe the inventory can already do, with the maximum number of crafts
+    pub fn craftable_now(&self, inventory: &ItemList) -> Vec<(&Item, Quantity)> {
+        inventory
+            .keys()
+            .filter_map(|item| self.used_in.get(&item.id))
+            .flatten()
+            .map(|(result_id, _)| &self.items[result_id])
+            .unique()
+            .filter_map(|item| {
+                let count = self.max_craftable(item, inventory);
+                (count > 0).then_some((item, count))
+            })
+            .sorted()
+            .collect()
+    }
+
+    // Items of the recipes whose name contains the text, case insensitive
+    pub fn find_items(&self, text: &str) -> Vec<&Item> {
+        let text = text.to_lowercase();
+
+        self.items
+            .values()
+            .filter(|item| item.name.to_lowercase().contains(&text))
+            .sorted()
+            .collect()
+    }
+
+    pub fn depth(&self, item: &Item) -> usize {
+        *self.depths.get(&item.id).unwrap_or(&0)
+    }
+
+    // Tarjan's strongly connected components, every component with more than one item
+    // (or an item needing itself) is a cycle
+    fn find_cycles(&mut self) {
+        struct State<'a> {
+            ingredients: &'a HashMap<i32, Vec<(i32, Quantity)>>,
+            index: usize,
+            indexes: HashMap<i32, usize>,
+            low_links: HashMap<i32, usize>,
+            stack: Vec<i32>,
+            on_stack: HashSet<i32>,
+            components: Vec<Vec<i32>>,
+        }
+
+        fn visit(state: &mut State, id: i32) {
+            state.indexes.insert(id, state.index);
+            state.low_links.insert(id, state.index);
+            state.index += 1;
+            state.stack.push(id);
+            state.on_stack.insert(id);
+
+            let ingredients = state.ingredients.get(&id).cloned().unwrap_or_default();
+            ingredients.iter().for_each(|(ingredient_id, _)| {
+                if !state.indexes.contains_key(ingredient_id) {
+                    visit(state, *ingredient_id);
+                    let low_link = cmp::min(state.low_links[&id], state.low_links[ingredient_id]);
+                    state.low_links.insert(id, low_link);
+                } else if state.on_stack.contains(ingredient_id) {
+                    let low_link = cmp::min(state.low_links[&id], state.indexes[ingredient_id]);
+                    state.low_links.insert(id, low_link);
+                }
+            });
+
+            if state.low_links[&id] == state.indexes[&id] {
+                let mut component = Vec::new();
+                while let Some(member) = state.stack.pop() {
+                    state.on_stack.remove(&member);
+                    component.push(member);
+                    if member == id {
+                        break;
+                    }
+                }
+                state.components.push(component);
+            }
+        }
+
+        let mut state = State {
+            ingredients: &self.ingredients,
+            index: 0,
+            indexes: HashMap::new(),
+            low_links: HashMap::new(),
+            stack: Vec::new(),
+            on_stack: HashSet::new(),
+            components: Vec::new(),
+        };
+
+        self.ingredients.keys().sorted().for_each(|id| {
+            if !state.indexes.contains_key(id) {
+                visit(&mut state, *id);
+            }
+        });
+
+        let components = state.components;
+        components.into_iter().for_each(|mut component| {
+            // Base ingredients are components too, without a recipe
+            let self_needed = component.len() == 1
+                && self
+                    .ingredients
+                    .get(&component[0])
+                    .is_some_and(|ingredients| {
+                        ingredients
+                            .iter()
+                            .any(|(ingredient_id, _)| *ingredient_id == component[0])
+                    });
+
+            if component.len() > 1 || self_needed {
+                component.sort();
+                self.cyclic_items.extend(&component);
+                self.cycles
+                    .push(component.iter().map(|id| self.items[id].clone()).collect());
+            }
+        });
+    }
+
+    fn compute_depths(&mut self) {
+        fn depth_of(graph: &RecipeGraph, depths: &mut HashMap<i32, usize>, id: i32) -> usize {
+            if let Some(depth) = depths.get(&id) {
+                return *depth;
+            }
+
+            let depth = match graph.ingredients.get(&id) {
+                Some(ingredients) if !graph.cyclic_items.contains(&id) => {
+                    1 + ingredients
+                        .iter()
+                        .map(|(ingredient_id, _)| depth_of(graph, depths, *ingredient_id))
+                        .max()
+                        .unwrap_or(0)
+                }
+                _ => 0,
+            };
+
+            depths.insert(id, depth);
+            depth
+        }
+
+        let mut depths = HashMap::new();
+        self.items.keys().for_each(|id| {
+            depth_of(self, &mut depths, *id);
+        });
+
+        self.depths = depths;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::RecipeGraph;
+    use crate::{
+        database::models::item::{Item, ItemList},
+        test_database::{
+            bakery::{bakery, graph, list, Bakery},
+            item, TestDatabase,
+        },
+    };
+
+    #[test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    fn full_recipe_splits_tiers_and_base_ingredients() {
+        let database = TestDatabase::new();
+        let connection = &mut database.connection();
+        let graph = RecipeGraph::load(connection);
+
+        let (wheat, water, flour, bread, cake, egg) = (
+            item(connection, 1),
+            item(connection, 2),
+            item(connection, 3),
+            item(connection, 4),
+            item(connection, 5),
+            item(connection, 6),
+        );
+
+        let (base_ingredients, steps) = graph.get_full_recipe(&cake, &2);
+
+        assert_eq!(
+            base_ingredients,
+            ItemList::with_items(BTreeMap::from([(wheat, 18), (water, 2), (egg, 4)]))
+        );
+        assert_eq!(
+            steps,
+            vec![
+                ItemList::with_items(BTreeMap::from([(flour.clone(), 4)])),
+                ItemList::with_items(BTreeMap::from([(bread, 2), (flour, 2)])),
+            ]
+        );
+    }
+
+    #[test]
+    fn craftable_now_counts_direct_crafts() {
+        let Bakery {
+            graph,
+            wheat,
+            water,
+            flour,
+            bread,
+            cake,
+        } = bakery();
+
+        let inventory = list(&[(&wheat, 10), (&water, 5), (&flour, 3)]);
+
+        assert_eq!(graph.max_craftable(&flour, &inventory), 3);
+        assert_eq!(graph.max_craftable(&bread, &inventory), 1);
+        assert_eq!(graph.max_craftable(&wheat, &inventory), 0);
+        assert_eq!(
+            graph.craftable_now(&inventory),
+            vec![(&flour, 3), (&bread, 1)]
+        );
+        // No Pain in stock for the Gâteau
+        assert_eq!(graph.max_craftable(&cake, &inventory), 0);
+        assert_eq!(graph.used_in(&flour), vec![(&cake, 1), (&bread, 2)]);
+    }
+
+    #[test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    fn cyclic_items_are_base_ingredients() {
+        let database = TestDatabase::new();
+        let connection = &mut database.connection();
+        let graph = RecipeGraph::load(connection);
+
+        let soul_stone = item(connection, 7);
+
+        assert!(graph.has_recipe(&soul_stone));
+        assert!(!graph.is_craftable(&soul_stone));
+
+        let (base_ingredients, steps) = graph.get_full_recipe(&soul_stone, &3);
+
+        assert_eq!(
+            base_ingredients,
+            ItemList::with_items(BTreeMap::from([(soul_stone, 3)]))
+        );
+        assert!(steps.is_empty());
+    }
+
+    #[test]
+    fn base_ingredients_are_not_cycles() {
+        let Bakery { wheat, flour, .. } = bakery();
+        let soul_stone = Item::new(7, "Pierre d'âme".to_owned(), 0, 1007);
+        let soul_fragment = Item::new(8, "Fragment d'âme".to_owned(), 0, 1008);
+
+        let graph = graph(&[
+            (&flour, &wheat, 3),
+            (&soul_stone, &soul_fragment, 1),
+            (&soul_fragment, &soul_stone, 2),
+        ]);
+
+        assert_eq!(
+            graph.cycles,
+            vec![vec![soul_stone.clone(), soul_fragment.clone()]]
+        );
+        assert!(!graph.has_recipe(&wheat));
+        assert!(graph.is_craftable(&flour));
+        assert!(graph.has_recipe(&soul_stone));
+        assert!(!graph.is_craftable(&soul_stone));
+    }
+}
@@ -5,6 +5,9 @@ use dotenvy::dotenv;
 use std::env;
 use tracing::{trace, trace_span};
 
+// The database worker and the background reads (recipes, search) share it
+const POOL_SIZE: u32 = 4;
+
 pub fn establish_pooled_connection() -> Pool<ConnectionManager<PgConnection>> {
     let span = trace_span!("establishing pooled connection");
     let _guard = span.enter();
@@ -19,7 +22,7 @@ pub fn establish_pooled_connection() -> Pool<ConnectionManager<PgConnection>> {
 
     trace!("Creating pool");
     Pool::builder()
-        .max_size(1)
+        .max_size(POOL_SIZE)
         .build(manager)
         .expect("Failed to create pool.")
 }
@@ -1,3 +1,11 @@
-use diesel::{define_sql_function, sql_types::*};
+use diesel::{define_sql_function, infix_operator, pg::Pg, sql_types::*};
 
 define_sql_function!(fn f_unaccent(x: Text) -> Text);
+define_sql_function!(fn nextval(sequence: Text) -> BigInt);
+
+// pg_trgm
+define_sql_function!(fn word_similarity(x: Text, y: Text) -> Float4);
+define_sql_function!(fn similarity(x: Text, y: Text) -> Float4);
+
+// left %> right: right is similar to a word of left
+infix_operator!(WordSimilar, " %> ", backend: Pg);
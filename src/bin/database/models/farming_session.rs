@@ -0,0 +1,23 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use lombok::AllArgsConstructor;
+
+#[derive(Queryable, Selectable, Identifiable, Debug, PartialEq, Eq, Clone)]
+#[diesel(table_name = crate::database::schema::farming_sessions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+// Time spent farming, in one zone when it is known
+pub struct FarmingSession {
+    pub id: i32,
+    pub profile_id: i32,
+    pub sub_area_id: Option<i32>,
+    pub started_at: NaiveDateTime,
+    pub stopped_at: Option<NaiveDateTime>,
+}
+
+#[derive(Insertable, AllArgsConstructor, Debug)]
+#[diesel(table_name = crate::database::schema::farming_sessions)]
+pub struct NewFarmingSession {
+    pub profile_id: i32,
+    pub sub_area_id: Option<i32>,
+    pub started_at: NaiveDateTime,
+}
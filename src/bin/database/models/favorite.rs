@@ -0,0 +1,28 @@
+use diesel::prelude::*;
+use lombok::AllArgsConstructor;
+
+#[derive(Queryable, Selectable, Identifiable, AllArgsConstructor, Debug, PartialEq, Clone)]
+#[diesel(table_name = crate::database::schema::favorites)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+// Bookmarked sub-area or map view
+pub struct Favorite {
+    pub id: i32,
+    pub profile_id: i32,
+    pub name: String,
+    pub sub_area_id: Option<i32>,
+    // Point of the full map at zoom 1 in the center of the view
+    pub center_x: Option<f32>,
+    pub center_y: Option<f32>,
+    pub scale: Option<f32>,
+}
+
+#[derive(Insertable, AllArgsConstructor, Debug)]
+#[diesel(table_name = crate::database::schema::favorites)]
+pub struct NewFavorite {
+    pub profile_id: i32,
+    pub name: String,
+    pub sub_area_id: Option<i32>,
+    pub center_x: Option<f32>,
+    pub center_y: Option<f32>,
+    pub scale: Option<f32>,
+}
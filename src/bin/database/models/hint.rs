@@ -0,0 +1,17 @@
+use super::hint_category::HintCategory;
+use diesel::prelude::*;
+use lombok::AllArgsConstructor;
+
+#[derive(
+    Queryable, Selectable, Identifiable, Insertable, Associations, AllArgsConstructor, Debug, Clone,
+)]
+#[diesel(table_name = crate::database::schema::hints)]
+#[diesel(belongs_to(HintCategory, foreign_key = category_id))]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Hint {
+    pub id: i32,
+    pub category_id: i32,
+    pub name: String,
+    pub x: i16,
+    pub y: i16,
+}
@@ -0,0 +1,12 @@
+use diesel::prelude::*;
+use lombok::AllArgsConstructor;
+
+#[derive(
+    Queryable, Selectable, Identifiable, Insertable, AllArgsConstructor, Debug, PartialEq, Eq, Clone,
+)]
+#[diesel(table_name = crate::database::schema::hint_categories)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct HintCategory {
+    pub id: i32,
+    pub name: String,
+}
@@ -0,0 +1,35 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use lombok::AllArgsConstructor;
+
+use super::item::Quantity;
+
+#[derive(Queryable, Selectable, Identifiable, Debug, Clone)]
+#[diesel(table_name = crate::database::schema::journal_entries)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+// One change of the inventory or the wish list
+pub struct JournalEntry {
+    pub id: i32,
+    pub profile_id: i32,
+    pub batch_id: i64,
+    pub created_at: NaiveDateTime,
+    pub item_id: i32,
+    pub target: String,
+    pub delta: Quantity,
+    pub reason: String,
+    pub undone: bool,
+    // Project of a wish list item
+    pub project_id: Option<i32>,
+}
+
+#[derive(Insertable, AllArgsConstructor, Debug)]
+#[diesel(table_name = crate::database::schema::journal_entries)]
+pub struct NewJournalEntry {
+    pub profile_id: i32,
+    pub batch_id: i64,
+    pub item_id: i32,
+    pub target: String,
+    pub delta: Quantity,
+    pub reason: String,
+    pub project_id: Option<i32>,
+}
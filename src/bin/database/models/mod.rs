@@ -1,10 +1,20 @@
 pub mod area;
 pub mod drop;
+pub mod farming_session;
+pub mod favorite;
+pub mod hint;
+pub mod hint_category;
 pub mod item;
+pub mod journal_entry;
 pub mod map;
 pub mod monster;
 pub mod monster_sub_area;
+pub mod price;
+pub mod profile;
+pub mod project;
 pub mod recipe;
+pub mod session_kill;
+pub mod session_loot;
 pub mod sub_area;
 pub mod user_ingredient;
 pub mod user_item;
@@ -27,7 +27,7 @@ impl Eq for Monster {}
 
 impl PartialOrd for Monster {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.name.cmp(&other.name))
+        Some(self.cmp(other))
     }
 }
 
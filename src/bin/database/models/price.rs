@@ -0,0 +1,16 @@
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use lombok::AllArgsConstructor;
+
+// Kamas, large enough for a whole guild order
+pub type Kamas = i64;
+
+#[derive(Queryable, Selectable, Insertable, AsChangeset, AllArgsConstructor, Debug, Clone)]
+#[diesel(table_name = crate::database::schema::prices)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Price {
+    pub item_id: i32,
+    pub lot_size: i16,
+    pub unit_price: Kamas,
+    pub price_date: NaiveDate,
+}
@@ -0,0 +1,19 @@
+use diesel::prelude::*;
+use lombok::AllArgsConstructor;
+
+#[derive(
+    Queryable, Selectable, Identifiable, AllArgsConstructor, Debug, PartialEq, Eq, Hash, Clone,
+)]
+#[diesel(table_name = crate::database::schema::profiles)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+// One wish list and inventory, for a team member or a character
+pub struct Profile {
+    pub id: i32,
+    pub name: String,
+}
+
+#[derive(Insertable, AllArgsConstructor, Debug)]
+#[diesel(table_name = crate::database::schema::profiles)]
+pub struct NewProfile {
+    pub name: String,
+}
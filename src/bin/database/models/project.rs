@@ -0,0 +1,26 @@
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use lombok::AllArgsConstructor;
+
+#[derive(
+    Queryable, Selectable, Identifiable, AllArgsConstructor, Debug, PartialEq, Eq, Hash, Clone,
+)]
+#[diesel(table_name = crate::database::schema::projects)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+// Named group of wish list items, like a gear set or a guild order
+pub struct Project {
+    pub id: i32,
+    pub profile_id: i32,
+    pub name: String,
+    pub priority: i32,
+    pub deadline: Option<NaiveDate>,
+}
+
+#[derive(Insertable, AllArgsConstructor, Debug)]
+#[diesel(table_name = crate::database::schema::projects)]
+pub struct NewProject {
+    pub profile_id: i32,
+    pub name: String,
+    pub priority: i32,
+    pub deadline: Option<NaiveDate>,
+}
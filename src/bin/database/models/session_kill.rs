@@ -0,0 +1,12 @@
+use diesel::prelude::*;
+use lombok::AllArgsConstructor;
+
+#[derive(Queryable, Selectable, Insertable, AllArgsConstructor, Debug, Clone)]
+#[diesel(table_name = crate::database::schema::session_kills)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+// Monsters killed during a farming session
+pub struct SessionKill {
+    pub session_id: i32,
+    pub monster_id: i32,
+    pub kills: i32,
+}
@@ -0,0 +1,14 @@
+use diesel::prelude::*;
+use lombok::AllArgsConstructor;
+
+use super::item::Quantity;
+
+#[derive(Queryable, Selectable, Insertable, AllArgsConstructor, Debug, Clone)]
+#[diesel(table_name = crate::database::schema::session_loot)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+// Items obtained during a farming session
+pub struct SessionLoot {
+    pub session_id: i32,
+    pub item_id: i32,
+    pub quantity: Quantity,
+}
@@ -1,6 +1,8 @@
 use diesel::prelude::*;
 use lombok::AllArgsConstructor;
 
+use super::item::Quantity;
+
 #[derive(
     Queryable,
     Selectable,
@@ -17,5 +19,6 @@ use lombok::AllArgsConstructor;
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct UserIngredient {
     pub item_id: i32,
-    pub quantity: i16,
+    pub quantity: Quantity,
+    pub profile_id: i32,
 }
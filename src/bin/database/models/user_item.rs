@@ -1,6 +1,8 @@
 use diesel::prelude::*;
 use lombok::AllArgsConstructor;
 
+use super::item::Quantity;
+
 #[derive(
     Queryable,
     Selectable,
@@ -18,5 +20,7 @@ use lombok::AllArgsConstructor;
 // Item wanted
 pub struct UserItem {
     pub item_id: i32,
-    pub quantity: i16,
+    pub quantity: Quantity,
+    pub profile_id: i32,
+    pub project_id: Option<i32>,
 }
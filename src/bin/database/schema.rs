@@ -14,6 +14,45 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    favorites (id) {
+        id -> Int4,
+        profile_id -> Int4,
+        name -> Varchar,
+        sub_area_id -> Nullable<Int4>,
+        center_x -> Nullable<Float4>,
+        center_y -> Nullable<Float4>,
+        scale -> Nullable<Float4>,
+    }
+}
+
+diesel::table! {
+    farming_sessions (id) {
+        id -> Int4,
+        profile_id -> Int4,
+        sub_area_id -> Nullable<Int4>,
+        started_at -> Timestamp,
+        stopped_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    hint_categories (id) {
+        id -> Int4,
+        name -> Varchar,
+    }
+}
+
+diesel::table! {
+    hints (id) {
+        id -> Int4,
+        category_id -> Int4,
+        name -> Varchar,
+        x -> Int2,
+        y -> Int2,
+    }
+}
+
 diesel::table! {
     items (id) {
         id -> Int4,
@@ -23,6 +62,21 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    journal_entries (id) {
+        id -> Int4,
+        profile_id -> Int4,
+        batch_id -> Int8,
+        created_at -> Timestamp,
+        item_id -> Int4,
+        target -> Varchar,
+        delta -> Int4,
+        reason -> Varchar,
+        undone -> Bool,
+        project_id -> Nullable<Int4>,
+    }
+}
+
 diesel::table! {
     maps (id) {
         id -> Int4,
@@ -47,11 +101,53 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    prices (item_id, lot_size, price_date) {
+        item_id -> Int4,
+        lot_size -> Int2,
+        unit_price -> Int8,
+        price_date -> Date,
+    }
+}
+
+diesel::table! {
+    profiles (id) {
+        id -> Int4,
+        name -> Varchar,
+    }
+}
+
+diesel::table! {
+    projects (id) {
+        id -> Int4,
+        profile_id -> Int4,
+        name -> Varchar,
+        priority -> Int4,
+        deadline -> Nullable<Date>,
+    }
+}
+
 diesel::table! {
     recipes (result_item_id, ingredient_item_id) {
         result_item_id -> Int4,
         ingredient_item_id -> Int4,
-        quantity -> Int2,
+        quantity -> Int4,
+    }
+}
+
+diesel::table! {
+    session_kills (session_id, monster_id) {
+        session_id -> Int4,
+        monster_id -> Int4,
+        kills -> Int4,
+    }
+}
+
+diesel::table! {
+    session_loot (session_id, item_id) {
+        session_id -> Int4,
+        item_id -> Int4,
+        quantity -> Int4,
     }
 }
 
@@ -64,36 +160,66 @@ diesel::table! {
 }
 
 diesel::table! {
-    user_ingredients (item_id) {
+    user_ingredients (profile_id, item_id) {
         item_id -> Int4,
-        quantity -> Int2,
+        quantity -> Int4,
+        profile_id -> Int4,
     }
 }
 
 diesel::table! {
-    user_items (item_id) {
+    user_items (profile_id, item_id) {
         item_id -> Int4,
-        quantity -> Int2,
+        quantity -> Int4,
+        profile_id -> Int4,
+        project_id -> Nullable<Int4>,
     }
 }
 
 diesel::joinable!(drops -> items (item_id));
 diesel::joinable!(drops -> monsters (monster_id));
+diesel::joinable!(favorites -> profiles (profile_id));
+diesel::joinable!(favorites -> sub_areas (sub_area_id));
+diesel::joinable!(farming_sessions -> profiles (profile_id));
+diesel::joinable!(farming_sessions -> sub_areas (sub_area_id));
+diesel::joinable!(hints -> hint_categories (category_id));
+diesel::joinable!(journal_entries -> items (item_id));
+diesel::joinable!(journal_entries -> profiles (profile_id));
+diesel::joinable!(journal_entries -> projects (project_id));
 diesel::joinable!(maps -> sub_areas (sub_area_id));
 diesel::joinable!(monsters_sub_areas -> monsters (monster_id));
 diesel::joinable!(monsters_sub_areas -> sub_areas (sub_area_id));
+diesel::joinable!(prices -> items (item_id));
+diesel::joinable!(projects -> profiles (profile_id));
+diesel::joinable!(session_kills -> farming_sessions (session_id));
+diesel::joinable!(session_kills -> monsters (monster_id));
+diesel::joinable!(session_loot -> farming_sessions (session_id));
+diesel::joinable!(session_loot -> items (item_id));
 diesel::joinable!(sub_areas -> areas (area_id));
 diesel::joinable!(user_ingredients -> items (item_id));
+diesel::joinable!(user_ingredients -> profiles (profile_id));
 diesel::joinable!(user_items -> items (item_id));
+diesel::joinable!(user_items -> profiles (profile_id));
+diesel::joinable!(user_items -> projects (project_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
     areas,
     drops,
+    favorites,
+    farming_sessions,
+    hint_categories,
+    hints,
     items,
+    journal_entries,
     maps,
     monsters,
     monsters_sub_areas,
+    prices,
+    profiles,
+    projects,
     recipes,
+    session_kills,
+    session_loot,
     sub_areas,
     user_ingredients,
     user_items,
@@ -8,8 +8,15 @@ use diesel::{insert_into, PgConnection, QueryResult, RunQueryDsl};
 use serde_json::{from_str, Value};
 
 use crate::database::models::{
-    drop::Drop, item::Item, map::Map, monster::Monster, monster_sub_area::MonsterSubArea,
-    recipe::Recipe, sub_area::SubArea,
+    drop::Drop,
+    hint::Hint,
+    hint_category::HintCategory,
+    item::{Item, Quantity},
+    map::Map,
+    monster::Monster,
+    monster_sub_area::MonsterSubArea,
+    recipe::Recipe,
+    sub_area::SubArea,
 };
 
 mod database;
@@ -29,6 +36,8 @@ fn main() {
             fill_areas(connection, &name_map);
             fill_sub_areas(connection, &name_map);
             fill_maps(connection, &name_map);
+            fill_hint_categories(connection, &name_map);
+            fill_hints(connection, &name_map);
             fill_items(connection, &name_map);
             fill_monsters(connection, &name_map);
             fill_drops(connection);
@@ -158,6 +167,71 @@ fn fill_maps(connection: &mut PgConnection, name_map: &HashMap<u32, String>) {
     println!("End fill_maps");
 }
 
+fn fill_hint_categories(connection: &mut PgConnection, name_map: &HashMap<u32, String>) {
+    use crate::database::schema::hint_categories;
+
+    println!("Starting fill_hint_categories");
+
+    fill_table(
+        "HintCategory.json",
+        |s| s.to_string(),
+        connection,
+        |v, connection| {
+            for category in v {
+                let category = category.as_object().unwrap();
+                let category = HintCategory::new(
+                    category["id"].as_i64().unwrap() as i32,
+                    name_map[&(category["nameId"].as_u64().unwrap() as u32)].to_owned(),
+                );
+
+                insert_into(hint_categories::table)
+                    .values(&category)
+                    .execute(connection)
+                    .unwrap();
+            }
+        },
+    );
+
+    println!("End fill_hint_categories");
+}
+
+fn fill_hints(connection: &mut PgConnection, name_map: &HashMap<u32, String>) {
+    use crate::database::schema::hints;
+
+    println!("Starting fill_hints");
+
+    fill_table(
+        "Hints.json",
+        |s| s.to_string(),
+        connection,
+        |v, connection| {
+            for hint in v {
+                let hint = hint.as_object().unwrap();
+                // Only the main world map is drawn
+                if hint["worldMapId"].as_i64().unwrap() == 1 {
+                    let hint = Hint::new(
+                        hint["id"].as_i64().unwrap() as i32,
+                        hint["categoryId"].as_i64().unwrap() as i32,
+                        name_map
+                            .get(&(hint["nameId"].as_u64().unwrap() as u32))
+                            .cloned()
+                            .unwrap_or_default(),
+                        hint["x"].as_i64().unwrap() as i16,
+                        hint["y"].as_i64().unwrap() as i16,
+                    );
+
+                    insert_into(hints::table)
+                        .values(&hint)
+                        .execute(connection)
+                        .unwrap();
+                }
+            }
+        },
+    );
+
+    println!("End fill_hints");
+}
+
 fn fill_items(connection: &mut PgConnection, name_map: &HashMap<u32, String>) {
     use crate::database::schema::items;
 
@@ -299,7 +373,7 @@ fn fill_recipes(connection: &mut PgConnection) {
                     .as_array()
                     .unwrap()
                     .iter()
-                    .map(|v| v.as_i64().unwrap() as i16);
+                    .map(|v| v.as_i64().unwrap() as Quantity);
 
                 let results_ingredients_quantities: Vec<_> = ingredient_ids
                     .zip(quantities)
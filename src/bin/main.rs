@@ -2,7 +2,11 @@
 #![allow(clippy::too_many_arguments)]
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 
+mod crafting;
 mod database;
+mod services;
+#[cfg(test)]
+mod test_database;
 mod windows;
 
 use crate::database::connection::establish_pooled_connection;
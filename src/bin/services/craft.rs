@@ -0,0 +1,321 @@
+use std::{cmp, collections::BTreeMap, fmt};
+
+use diesel::{delete, prelude::*, update, PgConnection};
+use itertools::Itertools;
+
+use crate::{
+    crafting::{planner::CraftPlan, recipe_graph::RecipeGraph},
+    database::models::{
+        item::{Item, ItemList, Quantity},
+        user_ingredient::UserIngredient,
+    },
+};
+
+#[derive(Debug)]
+pub enum CraftError {
+    NotCraftable(Item),
+    NotInWishList(Item),
+    // Base ingredients lacking, nothing is consumed
+    MissingIngredients(ItemList),
+    Database(diesel::result::Error),
+}
+
+impl From<diesel::result::Error> for CraftError {
+    fn from(error: diesel::result::Error) -> Self {
+        Self::Database(error)
+    }
+}
+
+impl fmt::Display for CraftError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotCraftable(item) => write!(f, "{} has no recipe", item.name),
+            Self::NotInWishList(item) => write!(f, "{} is not in the wish list", item.name),
+            Self::MissingIngredients(missing) => write!(
+                f,
+                "Missing ingredients: {}",
+                missing
+                    .iter()
+                    .map(|(item, quantity)| format!("{quantity} {}", item.name))
+                    .join(", ")
+            ),
+            Self::Database(error) => write!(f, "Craft not saved: {error}"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Craft {
+    pub item: Item,
+    // Never more than the wish list quantity
+    pub quantity: Quantity,
+    // Taken from the inventory
+    pub consumed: ItemList,
+    // Crafted on the way from what was consumed, so they are used right away
+    pub intermediates: ItemList,
+}
+
+// What crafting takes from the inventory, planned like the wish list in the resources tab so
+// intermediates in stock are used the same way. Returns the consumed items and the crafted
+// intermediates
+pub fn plan_craft(
+    recipe_graph: &RecipeGraph,
+    inventory: &ItemList,
+    item: &Item,
+    quantity: Quantity,
+) -> Result<(ItemList, ItemList), CraftError> {
+    if !recipe_graph.is_craftable(item) {
+        return Err(CraftError::NotCraftable(item.clone()));
+    }
+
+    let wish_list = ItemList::with_items(BTreeMap::from([(item.clone(), quantity)]));
+    let plan = CraftPlan::new(recipe_graph, &wish_list, inventory);
+
+    if !plan.missing.is_empty() {
+        return Err(CraftError::MissingIngredients(plan.missing));
+    }
+
+    let mut consumed = plan.intermediates_from_stock;
+    consumed.add_items(&plan.base_from_stock);
+
+    let mut intermediates = ItemList::new();
+    plan.tiers
+        .iter()
+        .for_each(|tier| intermediates.add_items(tier));
+    intermediates.retain(|intermediate, _| intermediate != item);
+
+    Ok((consumed, intermediates))
+}
+
+// Crafts from the saved inventory of the profile and removes the crafted quantity from its wish
+// list, all in one transaction
+pub fn craft(
+    connection: &mut PgConnection,
+    recipe_graph: &RecipeGraph,
+    profile_id: i32,
+    item: &Item,
+    quantity: Quantity,
+) -> Result<Craft, CraftError> {
+    use crate::database::schema::{items, user_ingredients, user_items};
+
+    connection.transaction(|connection| {
+        let wished = user_items::table
+            .filter(user_items::profile_id.eq(profile_id))
+            .filter(user_items::item_id.eq(item.id))
+            .select(user_items::quantity)
+            .first::<Quantity>(connection)
+            .optional()?
+            .unwrap_or(0);
+
+        let quantity = cmp::min(quantity, wished);
+        if quantity <= 0 {
+            return Err(CraftError::NotInWishList(item.clone()));
+        }
+
+        let inventory = ItemList::with_items(
+            user_ingredients::table
+                .inner_join(items::table)
+                .filter(user_ingredients::profile_id.eq(profile_id))
+                .load::<(UserIngredient, Item)>(connection)?
+                .into_iter()
+                .map(|(user_ingredient, item)| (item, user_ingredient.quantity))
+                .collect(),
+        );
+
+        let (consumed, intermediates) = plan_craft(recipe_graph, &inventory, item, quantity)?;
+
+        consumed.iter().try_for_each(|(ingredient, quantity)| {
+            let left = inventory[ingredient] - quantity;
+            let row = user_ingredients::table
+                .filter(user_ingredients::profile_id.eq(profile_id))
+                .filter(user_ingredients::item_id.eq(ingredient.id));
+
+            if left == 0 {
+                delete(row).execute(connection)
+            } else {
+                update(row)
+                    .set(user_ingredients::quantity.eq(left))
+                    .execute(connection)
+            }
+            .map(|_| ())
+        })?;
+
+        let wish_list_row = user_items::table
+            .filter(user_items::profile_id.eq(profile_id))
+            .filter(user_items::item_id.eq(item.id));
+
+        if wished == quantity {
+            delete(wish_list_row).execute(connection)?;
+        } else {
+            update(wish_list_row)
+                .set(user_items::quantity.eq(wished - quantity))
+                .execute(connection)?;
+        }
+
+        Ok(Craft {
+            item: item.clone(),
+            quantity,
+            consumed,
+            intermediates,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use diesel::{insert_into, prelude::*};
+
+    use super::{craft, plan_craft, CraftError};
+    use crate::{
+        crafting::recipe_graph::RecipeGraph,
+        database::{
+            models::{item::ItemList, user_ingredient::UserIngredient, user_item::UserItem},
+            schema::{user_ingredients, user_items},
+        },
+        test_database::{
+            bakery::{bakery, list, Bakery},
+            item, TestDatabase,
+        },
+    };
+
+    #[test]
+    fn shared_intermediates_are_planned_like_the_wish_list() {
+        let Bakery {
+            graph,
+            wheat,
+            water,
+            flour,
+            bread,
+            cake,
+        } = bakery();
+
+        // The Pain in stock covers one Gâteau, the other Pain and 3 Farine are crafted
+        let inventory = list(&[(&bread, 1), (&flour, 1), (&wheat, 9), (&water, 1)]);
+        let (consumed, intermediates) = plan_craft(&graph, &inventory, &cake, 2).unwrap();
+
+        assert_eq!(
+            consumed,
+            list(&[(&bread, 1), (&flour, 1), (&wheat, 9), (&water, 1)])
+        );
+        assert_eq!(intermediates, list(&[(&bread, 1), (&flour, 3)]));
+    }
+
+    #[test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    fn intermediates_in_stock_are_used_before_crafting_them() {
+        let database = TestDatabase::new();
+        let connection = &mut database.connection();
+        let graph = RecipeGraph::load(connection);
+
+        let (wheat, water, flour, bread) = (
+            item(connection, 1),
+            item(connection, 2),
+            item(connection, 3),
+            item(connection, 4),
+        );
+
+        let inventory = ItemList::with_items(BTreeMap::from([
+            (flour.clone(), 1),
+            (wheat.clone(), 10),
+            (water.clone(), 1),
+        ]));
+
+        // 2 Farine and 1 Eau, the missing Farine is crafted from 3 Blé
+        let (consumed, intermediates) = plan_craft(&graph, &inventory, &bread, 1).unwrap();
+
+        assert_eq!(
+            consumed,
+            ItemList::with_items(BTreeMap::from([(flour.clone(), 1), (wheat, 3), (water, 1)]))
+        );
+        assert_eq!(
+            intermediates,
+            ItemList::with_items(BTreeMap::from([(flour, 1)]))
+        );
+    }
+
+    #[test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    fn missing_ingredients_and_cycles_are_errors() {
+        let database = TestDatabase::new();
+        let connection = &mut database.connection();
+        let graph = RecipeGraph::load(connection);
+
+        let (wheat, water, bread, fragment) = (
+            item(connection, 1),
+            item(connection, 2),
+            item(connection, 4),
+            item(connection, 8),
+        );
+
+        let inventory = ItemList::with_items(BTreeMap::from([(wheat.clone(), 2)]));
+
+        match plan_craft(&graph, &inventory, &bread, 1) {
+            Err(CraftError::MissingIngredients(missing)) => assert_eq!(
+                missing,
+                ItemList::with_items(BTreeMap::from([(wheat, 4), (water, 1)]))
+            ),
+            result => panic!("unexpected {result:?}"),
+        }
+
+        // Cyclic recipes are not expanded
+        assert!(matches!(
+            plan_craft(&graph, &inventory, &fragment, 1),
+            Err(CraftError::NotCraftable(_))
+        ));
+    }
+
+    #[test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    fn craft_updates_inventory_and_wish_list_together() {
+        let database = TestDatabase::new();
+        let connection = &mut database.connection();
+        let graph = RecipeGraph::load(connection);
+
+        let (wheat, water, flour) = (
+            item(connection, 1),
+            item(connection, 2),
+            item(connection, 3),
+        );
+
+        insert_into(user_items::table)
+            .values(UserItem::new(flour.id, 3, 1, None))
+            .execute(connection)
+            .unwrap();
+        insert_into(user_ingredients::table)
+            .values(vec![
+                UserIngredient::new(wheat.id, 6, 1),
+                UserIngredient::new(water.id, 1, 1),
+            ])
+            .execute(connection)
+            .unwrap();
+
+        let inventory = |connection: &mut PgConnection| {
+            user_ingredients::table
+                .select((user_ingredients::item_id, user_ingredients::quantity))
+                .order(user_ingredients::item_id)
+                .load::<(i32, i32)>(connection)
+                .unwrap()
+        };
+
+        // 3 Farine need 9 Blé, nothing changes
+        assert!(matches!(
+            craft(connection, &graph, 1, &flour, 3),
+            Err(CraftError::MissingIngredients(_))
+        ));
+        assert_eq!(inventory(connection), vec![(1, 6), (2, 1)]);
+
+        let crafted = craft(connection, &graph, 1, &flour, 2).unwrap();
+
+        assert_eq!(crafted.quantity, 2);
+        assert_eq!(inventory(connection), vec![(2, 1)]);
+        assert_eq!(
+            user_items::table
+                .select(user_items::quantity)
+                .first::<i32>(connection)
+                .unwrap(),
+            1
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::mpsc::{self, Sender},
+    thread,
+};
+
+use diesel::{
+    r2d2::{ConnectionManager, Pool},
+    PgConnection,
+};
+use egui::Context;
+use tracing::{error, trace_span};
+
+type Job = Box<dyn FnOnce(&mut PgConnection) + Send>;
+
+// Every write goes through this queue so they land in the order they were made,
+// the queries run on their own thread instead of blocking the async executor
+#[derive(Clone)]
+pub struct DatabaseWorker {
+    job_tx: Sender<Job>,
+}
+
+impl DatabaseWorker {
+    pub fn new(pool: Pool<ConnectionManager<PgConnection>>) -> Self {
+        let (job_tx, job_rx) = mpsc::channel::<Job>();
+
+        thread::Builder::new()
+            .name("database worker".to_owned())
+            .spawn(move || {
+                job_rx.into_iter().for_each(|job| {
+                    let span = trace_span!("database job");
+                    let _guard = span.enter();
+
+                    let mut connection = match pool.get() {
+                        Ok(connection) => connection,
+                        Err(pool_error) => {
+                            error!("No database connection for the job: {pool_error}");
+                            return;
+                        }
+                    };
+
+                    // A failing job must not stop the ones queued after it
+                    if panic::catch_unwind(AssertUnwindSafe(|| job(&mut connection))).is_err() {
+                        error!("Database job panicked");
+                    }
+                });
+            })
+            .expect("Failed to start the database worker");
+
+        Self { job_tx }
+    }
+
+    // Only for jobs that can't fail, the others reply their result with query
+    pub fn execute(&self, job: impl FnOnce(&mut PgConnection) + Send + 'static) {
+        self.job_tx
+            .send(Box::new(job))
+            .expect("Database worker stopped");
+    }
+
+    // The result is sent to reply_tx once every job queued before is done
+    pub fn query<T: Send + 'static>(
+        &self,
+        ctx: &Context,
+        reply_tx: Sender<T>,
+        job: impl FnOnce(&mut PgConnection) -> T + Send + 'static,
+    ) {
+        let ctx = ctx.clone();
+
+        self.execute(move |connection| {
+            if reply_tx.send(job(connection)).is_ok() {
+                ctx.request_repaint();
+            }
+        });
+    }
+}
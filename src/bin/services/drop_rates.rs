@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+
+use diesel::{prelude::*, PgConnection};
+use itertools::Itertools;
+
+use crate::database::models::{
+    drop::Drop,
+    farming_session::FarmingSession,
+    item::{Item, Quantity},
+    monster::Monster,
+    session_kill::SessionKill,
+    session_loot::SessionLoot,
+};
+
+// Kills and loot of one finished session
+pub struct SessionRecord {
+    pub seconds: f32,
+    // monster id -> kills
+    pub kills: HashMap<i32, i32>,
+    // item id -> quantity
+    pub loot: HashMap<i32, Quantity>,
+}
+
+pub struct FarmEstimate<'a> {
+    pub monster: &'a Monster,
+    // Items per kill
+    pub drop_rate: f32,
+    pub kills: f32,
+    // None until a session has been timed
+    pub seconds: Option<f32>,
+}
+
+// Drop rates seen in our own farming sessions, the game data has none
+#[derive(Default, Clone)]
+pub struct DropRates {
+    // (monster id, item id) -> items per kill
+    rates: HashMap<(i32, i32), f32>,
+    seconds_per_kill: Option<f32>,
+}
+
+impl DropRates {
+    pub fn load(connection: &mut PgConnection) -> Self {
+        use crate::database::schema::{drops, farming_sessions, session_kills, session_loot};
+
+        let sessions = farming_sessions::table
+            .filter(farming_sessions::stopped_at.is_not_null())
+            .select(FarmingSession::as_select())
+            .load(connection)
+            .unwrap();
+        let session_ids = sessions.iter().map(|session| session.id).collect_vec();
+
+        let kills = session_kills::table
+            .filter(session_kills::session_id.eq_any(&session_ids))
+            .select(SessionKill::as_select())
+            .load(connection)
+            .unwrap();
+        let loot = session_loot::table
+            .filter(session_loot::session_id.eq_any(&session_ids))
+            .select(SessionLoot::as_select())
+            .load(connection)
+            .unwrap();
+
+        let monster_ids = kills
+            .iter()
+            .map(|kill| kill.monster_id)
+            .unique()
+            .collect_vec();
+        let mut monsters_drops: HashMap<i32, Vec<i32>> = HashMap::new();
+        drops::table
+            .filter(drops::monster_id.eq_any(&monster_ids))
+            .select(Drop::as_select())
+            .load(connection)
+            .unwrap()
+            .into_iter()
+            .for_each(|drop| {
+                monsters_drops
+                    .entry(drop.monster_id)
+                    .or_default()
+                    .push(drop.item_id)
+            });
+
+        let records = sessions
+            .iter()
+            .map(|session| SessionRecord {
+                seconds: session
+                    .stopped_at
+                    .map(|stopped_at| (stopped_at - session.started_at).num_seconds() as f32)
+                    .unwrap_or(0f32),
+                kills: kills
+                    .iter()
+                    .filter(|kill| kill.session_id == session.id)
+                    .map(|kill| (kill.monster_id, kill.kills))
+                    .collect(),
+                loot: loot
+                    .iter()
+                    .filter(|loot| loot.session_id == session.id)
+                    .map(|loot| (loot.item_id, loot.quantity))
+                    .collect(),
+            })
+            .collect_vec();
+
+        Self::from_sessions(&records, &monsters_drops)
+    }
+
+    // The loot of a session is shared between the killed monsters dropping the item, by kills.
+    // A monster killed without getting the item lowers its rate. monsters_drops is
+    // monster id -> item ids
+    pub fn from_sessions(
+        sessions: &[SessionRecord],
+        monsters_drops: &HashMap<i32, Vec<i32>>,
+    ) -> Self {
+        let mut kills: HashMap<(i32, i32), f32> = HashMap::new();
+        let mut loot: HashMap<(i32, i32), f32> = HashMap::new();
+        let mut total_kills = 0f32;
+        let mut total_seconds = 0f32;
+
+        sessions.iter().for_each(|session| {
+            total_seconds += session.seconds;
+            total_kills += session.kills.values().sum::<i32>() as f32;
+
+            let mut droppers: HashMap<i32, Vec<(i32, f32)>> = HashMap::new();
+            session
+                .kills
+                .iter()
+                .for_each(|(monster_id, monster_kills)| {
+                    monsters_drops
+                        .get(monster_id)
+                        .into_iter()
+                        .flatten()
+                        .for_each(|item_id| {
+                            droppers
+                                .entry(*item_id)
+                                .or_default()
+                                .push((*monster_id, *monster_kills as f32));
+                        });
+                });
+
+            droppers.iter().for_each(|(item_id, monsters)| {
+                let item_kills = monsters.iter().map(|(_, kills)| kills).sum::<f32>();
+                let item_loot = *session.loot.get(item_id).unwrap_or(&0) as f32;
+
+                monsters.iter().for_each(|(monster_id, monster_kills)| {
+                    *kills.entry((*monster_id, *item_id)).or_default() += monster_kills;
+                    if item_kills > 0f32 {
+                        *loot.entry((*monster_id, *item_id)).or_default() +=
+                            item_loot * monster_kills / item_kills;
+                    }
+                });
+            });
+        });
+
+        let rates = kills
+            .into_iter()
+            .filter(|(_, kills)| *kills > 0f32)
+            .map(|(key, kills)| (key, loot.get(&key).copied().unwrap_or(0f32) / kills))
+            .collect();
+
+        Self {
+            rates,
+            seconds_per_kill: (total_kills > 0f32).then(|| total_seconds / total_kills),
+        }
+    }
+
+    pub fn rate(&self, monster: &Monster, item: &Item) -> Option<f32> {
+        self.rates.get(&(monster.id, item.id)).copied()
+    }
+
+    // Using the monster with the best observed rate
+    pub fn farm_estimate<'a>(
+        &self,
+        item: &Item,
+        quantity: Quantity,
+        monsters: impl IntoIterator<Item = &'a Monster>,
+    ) -> Option<FarmEstimate<'a>> {
+        let (monster, drop_rate) = monsters
+            .into_iter()
+            .filter_map(|monster| {
+                self.rate(monster, item)
+                    .filter(|rate| *rate > 0f32)
+                    .map(|rate| (monster, rate))
+            })
+            .max_by(|(_, rate), (_, other_rate)| rate.total_cmp(other_rate))?;
+
+        let kills = quantity as f32 / drop_rate;
+
+        Some(FarmEstimate {
+            monster,
+            drop_rate,
+            kills,
+            seconds: self
+                .seconds_per_kill
+                .map(|seconds_per_kill| kills * seconds_per_kill),
+        })
+    }
+}
+
+// 3900 -> "1 h 05 min"
+pub fn format_duration(seconds: f32) -> String {
+    let minutes = (seconds / 60f32).ceil() as i64;
+
+    if minutes < 60 {
+        format!("{minutes} min")
+    } else {
+        format!("{} h {:02} min", minutes / 60, minutes % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{format_duration, DropRates, SessionRecord};
+    use crate::database::models::{item::Item, monster::Monster};
+
+    #[test]
+    fn loot_is_shared_between_monsters_by_kills() {
+        let wool = Item::new(10, "Laine de Bouftou".to_owned(), 0, 10);
+        let gobball = Monster::new(1, "Bouftou".to_owned());
+        let black_gobbly = Monster::new(2, "Boufton noir".to_owned());
+
+        let monsters_drops = HashMap::from([(1, vec![10]), (2, vec![10])]);
+        let sessions = [
+            SessionRecord {
+                seconds: 600f32,
+                kills: HashMap::from([(1, 30), (2, 10)]),
+                loot: HashMap::from([(10, 20)]),
+            },
+            // Only Bouftous, without any wool
+            SessionRecord {
+                seconds: 200f32,
+                kills: HashMap::from([(1, 10)]),
+                loot: HashMap::new(),
+            },
+        ];
+
+        let drop_rates = DropRates::from_sessions(&sessions, &monsters_drops);
+
+        // 15 wools for 40 kills
+        assert_eq!(drop_rates.rate(&gobball, &wool), Some(15f32 / 40f32));
+        assert_eq!(drop_rates.rate(&black_gobbly, &wool), Some(0.5));
+
+        let estimate = drop_rates
+            .farm_estimate(&wool, 10, [&gobball, &black_gobbly])
+            .unwrap();
+
+        assert_eq!(estimate.monster, &black_gobbly);
+        assert_eq!(estimate.kills, 20f32);
+        // 800 seconds for 50 kills
+        assert_eq!(estimate.seconds, Some(320f32));
+    }
+
+    #[test]
+    fn unknown_rates_give_no_estimate() {
+        let wool = Item::new(10, "Laine de Bouftou".to_owned(), 0, 10);
+        let gobball = Monster::new(1, "Bouftou".to_owned());
+
+        let drop_rates = DropRates::default();
+
+        assert_eq!(drop_rates.rate(&gobball, &wool), None);
+        assert!(drop_rates.farm_estimate(&wool, 10, [&gobball]).is_none());
+    }
+
+    #[test]
+    fn durations_are_rounded_up_to_the_minute() {
+        assert_eq!(format_duration(59f32), "1 min");
+        assert_eq!(format_duration(3900f32), "1 h 05 min");
+    }
+}
@@ -0,0 +1,262 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::database::models::{item::Item, sub_area::SubArea};
+
+// Items per kill assumed for monsters never farmed. The game data has no drop rate and most
+// resources drop well under once a fight, so sub-areas with rates seen in sessions are preferred
+pub const UNKNOWN_DROP_RATE: f32 = 0.1;
+
+// A missing ingredient dropped in a sub-area
+pub struct DropSource {
+    pub sub_area: SubArea,
+    pub item: Item,
+    // Items per kill seen in the farming sessions, None when never observed
+    pub drop_rate: Option<f32>,
+    // Harder monsters make the sub-area cost more. The imported monsters have no level yet,
+    // None costs nothing so every sub-area is weighted the same
+    pub monster_level: Option<i16>,
+}
+
+pub struct RouteStep {
+    pub sub_area: SubArea,
+    // Items first covered by this sub-area
+    pub items: Vec<Item>,
+    // Items counted with UNKNOWN_DROP_RATE in this sub-area
+    pub unobserved: Vec<Item>,
+}
+
+// Greedy weighted set cover: the sub-area with the best drop rates of the items not covered yet
+// for its cost is taken until every item is covered, then the sub-areas are ordered by going to
+// the closest one each time. positions are the map coordinates of the sub-areas
+pub fn plan_route(sources: &[DropSource], positions: &HashMap<i32, (f32, f32)>) -> Vec<RouteStep> {
+    // Sorted by id so equal scores always pick the same sub-area
+    let mut sub_areas: BTreeMap<i32, (&SubArea, Vec<&DropSource>)> = BTreeMap::new();
+    sources.iter().for_each(|source| {
+        sub_areas
+            .entry(source.sub_area.id)
+            .or_insert_with(|| (&source.sub_area, Vec::new()))
+            .1
+            .push(source);
+    });
+
+    let mut uncovered: BTreeSet<&Item> = sources.iter().map(|source| &source.item).collect();
+    let mut steps = Vec::new();
+
+    while !uncovered.is_empty() {
+        let best = sub_areas
+            .values()
+            .filter_map(|(sub_area, sub_area_sources)| {
+                let mut drop_rates: BTreeMap<&Item, f32> = BTreeMap::new();
+                let mut observed: BTreeSet<&Item> = BTreeSet::new();
+                let mut max_level = 0;
+
+                sub_area_sources
+                    .iter()
+                    .filter(|source| uncovered.contains(&source.item))
+                    .for_each(|source| {
+                        let drop_rate = drop_rates.entry(&source.item).or_default();
+                        *drop_rate = drop_rate.max(source.drop_rate.unwrap_or(UNKNOWN_DROP_RATE));
+                        if source.drop_rate.is_some() {
+                            observed.insert(&source.item);
+                        }
+                        max_level = max_level.max(source.monster_level.unwrap_or(0));
+                    });
+
+                if drop_rates.is_empty() {
+                    return None;
+                }
+
+                let score = drop_rates.values().sum::<f32>() / (1f32 + max_level as f32 / 100f32);
+                let items = drop_rates.into_keys().collect::<Vec<_>>();
+                let unobserved = items
+                    .iter()
+                    .filter(|item| !observed.contains(*item))
+                    .map(|item| (*item).clone())
+                    .collect::<Vec<_>>();
+                Some((score, *sub_area, items, unobserved))
+            })
+            .fold(
+                None,
+                |best: Option<(f32, &SubArea, Vec<&Item>, Vec<Item>)>, candidate| match best {
+                    Some(best) if best.0 >= candidate.0 => Some(best),
+                    _ => Some(candidate),
+                },
+            );
+
+        let Some((_, sub_area, items, unobserved)) = best else {
+            break;
+        };
+
+        items.iter().for_each(|item| {
+            uncovered.remove(item);
+        });
+        steps.push(RouteStep {
+            sub_area: sub_area.clone(),
+            items: items.into_iter().cloned().collect(),
+            unobserved,
+        });
+    }
+
+    order_by_distance(steps, positions)
+}
+
+// Starts from the sub-area covering the most, the ones without position are done last
+fn order_by_distance(
+    mut steps: Vec<RouteStep>,
+    positions: &HashMap<i32, (f32, f32)>,
+) -> Vec<RouteStep> {
+    if steps.is_empty() {
+        return steps;
+    }
+
+    let mut ordered = vec![steps.remove(0)];
+
+    while let Some(last_position) = ordered
+        .last()
+        .and_then(|step| positions.get(&step.sub_area.id))
+    {
+        let closest = steps
+            .iter()
+            .enumerate()
+            .filter_map(|(index, step)| {
+                positions.get(&step.sub_area.id).map(|(x, y)| {
+                    let distance = (x - last_position.0).powi(2) + (y - last_position.1).powi(2);
+                    (index, distance)
+                })
+            })
+            .fold(
+                None,
+                |closest: Option<(usize, f32)>, candidate| match closest {
+                    Some(closest) if closest.1 <= candidate.1 => Some(closest),
+                    _ => Some(candidate),
+                },
+            );
+
+        match closest {
+            Some((index, _)) => ordered.push(steps.remove(index)),
+            None => break,
+        }
+    }
+
+    ordered.extend(steps);
+    ordered
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{plan_route, DropSource};
+    use crate::database::models::{item::Item, sub_area::SubArea};
+
+    fn source(sub_area: &SubArea, item: &Item, drop_rate: f32) -> DropSource {
+        DropSource {
+            sub_area: sub_area.clone(),
+            item: item.clone(),
+            drop_rate: Some(drop_rate),
+            monster_level: None,
+        }
+    }
+
+    fn sub_area_names(sources: &[DropSource], positions: &HashMap<i32, (f32, f32)>) -> Vec<String> {
+        plan_route(sources, positions)
+            .into_iter()
+            .map(|step| step.sub_area.name)
+            .collect()
+    }
+
+    #[test]
+    fn fewest_sub_areas_cover_every_item() {
+        let wheat = Item::new(1, "Blé".to_owned(), 0, 1);
+        let wool = Item::new(2, "Laine".to_owned(), 0, 2);
+        let leather = Item::new(3, "Cuir".to_owned(), 0, 3);
+
+        let fields = SubArea::new(1, "Champs".to_owned(), 1);
+        let plains = SubArea::new(2, "Plaines".to_owned(), 1);
+        let forest = SubArea::new(3, "Forêt".to_owned(), 1);
+
+        let sources = vec![
+            source(&fields, &wheat, 1f32),
+            source(&plains, &wheat, 1f32),
+            source(&plains, &wool, 1f32),
+            source(&plains, &leather, 1f32),
+            source(&forest, &leather, 1f32),
+        ];
+
+        let route = plan_route(&sources, &HashMap::new());
+
+        assert_eq!(route.len(), 1);
+        assert_eq!(route[0].sub_area, plains);
+        assert_eq!(route[0].items, vec![wheat, leather, wool]);
+    }
+
+    #[test]
+    fn better_drop_rates_win() {
+        let wheat = Item::new(1, "Blé".to_owned(), 0, 1);
+        let wool = Item::new(2, "Laine".to_owned(), 0, 2);
+
+        let fields = SubArea::new(1, "Champs".to_owned(), 1);
+        let plains = SubArea::new(2, "Plaines".to_owned(), 1);
+        let forest = SubArea::new(3, "Forêt".to_owned(), 1);
+
+        let sources = vec![
+            source(&fields, &wheat, 0.1),
+            source(&fields, &wool, 0.1),
+            source(&plains, &wheat, 0.5),
+            source(&forest, &wool, 0.5),
+        ];
+
+        let positions = HashMap::from([(2, (0f32, 0f32)), (3, (1f32, 0f32))]);
+
+        assert_eq!(sub_area_names(&sources, &positions), ["Plaines", "Forêt"]);
+    }
+
+    #[test]
+    fn route_goes_to_the_closest_sub_area() {
+        let items = (1..=3)
+            .map(|id| Item::new(id, format!("Item {id}"), 0, id))
+            .collect::<Vec<_>>();
+        let sub_areas = (1..=3)
+            .map(|id| SubArea::new(id, format!("Zone {id}"), 1))
+            .collect::<Vec<_>>();
+
+        let sources = vec![
+            source(&sub_areas[0], &items[0], 1f32),
+            source(&sub_areas[1], &items[1], 1f32),
+            source(&sub_areas[2], &items[2], 1f32),
+        ];
+
+        let positions = HashMap::from([(1, (0f32, 0f32)), (2, (10f32, 0f32)), (3, (2f32, 0f32))]);
+
+        assert_eq!(
+            sub_area_names(&sources, &positions),
+            ["Zone 1", "Zone 3", "Zone 2"]
+        );
+    }
+
+    #[test]
+    fn observed_drop_rates_beat_unknown_ones() {
+        let wheat = Item::new(1, "Blé".to_owned(), 0, 1);
+        let wool = Item::new(2, "Laine".to_owned(), 0, 2);
+
+        let fields = SubArea::new(1, "Champs".to_owned(), 1);
+        let plains = SubArea::new(2, "Plaines".to_owned(), 1);
+
+        let unknown = |sub_area: &SubArea, item: &Item| DropSource {
+            drop_rate: None,
+            ..source(sub_area, item, 0f32)
+        };
+        let sources = vec![
+            unknown(&fields, &wheat),
+            source(&plains, &wheat, 0.2),
+            unknown(&plains, &wool),
+        ];
+
+        let route = plan_route(&sources, &HashMap::new());
+
+        assert_eq!(route.len(), 1);
+        assert_eq!(route[0].sub_area, plains);
+        assert_eq!(route[0].items, vec![wheat, wool.clone()]);
+        assert_eq!(route[0].unobserved, vec![wool]);
+    }
+}
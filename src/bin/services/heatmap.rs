@@ -0,0 +1,113 @@
+use std::{collections::HashMap, fmt};
+
+use crate::database::models::{
+    item::{Item, Quantity},
+    sub_area::SubArea,
+};
+
+use super::farming_route::{DropSource, UNKNOWN_DROP_RATE};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeatmapMetric {
+    // Different missing ingredients dropped in the sub-area
+    MissingIngredients,
+    MissingQuantity,
+    // Kills to get every missing ingredient of the sub-area there
+    ExpectedKills,
+}
+
+impl HeatmapMetric {
+    pub const ALL: [Self; 3] = [
+        Self::MissingIngredients,
+        Self::MissingQuantity,
+        Self::ExpectedKills,
+    ];
+}
+
+impl fmt::Display for HeatmapMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingIngredients => write!(f, "Missing ingredients"),
+            Self::MissingQuantity => write!(f, "Missing quantity"),
+            Self::ExpectedKills => write!(f, "Expected kills"),
+        }
+    }
+}
+
+// Score of each sub-area dropping a missing ingredient. An item dropped by several monsters of a
+// sub-area counts once, with its best drop rate, UNKNOWN_DROP_RATE when never observed
+pub fn sub_area_scores<'a>(
+    sources: &'a [DropSource],
+    missing: &HashMap<&Item, Quantity>,
+    metric: HeatmapMetric,
+) -> HashMap<&'a SubArea, f32> {
+    let mut best_rates: HashMap<(&SubArea, &Item), f32> = HashMap::new();
+    sources.iter().for_each(|source| {
+        let rate = best_rates
+            .entry((&source.sub_area, &source.item))
+            .or_insert(0f32);
+        *rate = rate.max(source.drop_rate.unwrap_or(UNKNOWN_DROP_RATE));
+    });
+
+    let mut scores = HashMap::new();
+    best_rates
+        .into_iter()
+        .for_each(|((sub_area, item), drop_rate)| {
+            let quantity = *missing.get(item).unwrap_or(&0) as f32;
+            let score = match metric {
+                HeatmapMetric::MissingIngredients => 1f32,
+                HeatmapMetric::MissingQuantity => quantity,
+                HeatmapMetric::ExpectedKills if drop_rate > 0f32 => quantity / drop_rate,
+                HeatmapMetric::ExpectedKills => 0f32,
+            };
+            *scores.entry(sub_area).or_insert(0f32) += score;
+        });
+
+    scores
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{sub_area_scores, HeatmapMetric};
+    use crate::{
+        database::models::{item::Item, sub_area::SubArea},
+        services::farming_route::DropSource,
+    };
+
+    #[test]
+    fn items_count_once_per_sub_area_with_their_best_rate() {
+        let wool = Item::new(10, "Laine de Bouftou".to_owned(), 0, 10);
+        let leather = Item::new(11, "Cuir de Bouftou".to_owned(), 0, 11);
+        let fields = SubArea::new(1, "Champs".to_owned(), 1);
+        let plains = SubArea::new(2, "Plaines".to_owned(), 1);
+
+        let source = |sub_area: &SubArea, item: &Item, drop_rate| DropSource {
+            sub_area: sub_area.clone(),
+            item: item.clone(),
+            drop_rate: Some(drop_rate),
+            monster_level: None,
+        };
+        let sources = [
+            source(&fields, &wool, 0.5),
+            // Another monster of the sub-area dropping wool
+            source(&fields, &wool, 0.25),
+            source(&fields, &leather, 0.1),
+            source(&plains, &wool, 1f32),
+        ];
+        let missing = HashMap::from([(&wool, 10), (&leather, 2)]);
+
+        let ingredients = sub_area_scores(&sources, &missing, HeatmapMetric::MissingIngredients);
+        assert_eq!(ingredients[&fields], 2f32);
+        assert_eq!(ingredients[&plains], 1f32);
+
+        let quantity = sub_area_scores(&sources, &missing, HeatmapMetric::MissingQuantity);
+        assert_eq!(quantity[&fields], 12f32);
+        assert_eq!(quantity[&plains], 10f32);
+
+        let kills = sub_area_scores(&sources, &missing, HeatmapMetric::ExpectedKills);
+        assert_eq!(kills[&fields], 40f32);
+        assert_eq!(kills[&plains], 10f32);
+    }
+}
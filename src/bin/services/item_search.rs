@@ -0,0 +1,310 @@
+use std::{cmp::Ordering, collections::HashSet};
+
+use diesel::{prelude::*, sql_query, sql_types::Bool, PgConnection};
+use itertools::Itertools;
+
+use crate::database::models::item::Item;
+
+pub const SEARCH_PAGE_SIZE: usize = 100;
+
+// Only resources, equipments and consumables
+const CATEGORY_SEARCHED: [i16; 3] = [0, 1, 2];
+// Placeholder image of unreleased items
+const IMAGE_ID_EXCLUDED: i32 = 89042;
+// Same as pg_trgm.word_similarity_threshold
+const WORD_SIMILARITY_THRESHOLD: f32 = 0.6;
+
+#[derive(QueryableByName)]
+struct ExtensionInstalled {
+    #[diesel(sql_type = Bool)]
+    installed: bool,
+}
+
+pub enum ItemSearch {
+    // Ranked by the database with pg_trgm
+    Trigram,
+    // Without pg_trgm every searchable item is loaded once and ranked here, with its folded name
+    InProcess(Vec<(Item, String)>),
+}
+
+impl ItemSearch {
+    pub fn new(connection: &mut PgConnection) -> Self {
+        use crate::database::schema::items::{self, *};
+
+        let trigram_installed = sql_query(
+            "SELECT EXISTS (SELECT 1 FROM pg_extension WHERE extname = 'pg_trgm') AS installed",
+        )
+        .get_result::<ExtensionInstalled>(connection)
+        .map(|extension| extension.installed)
+        .unwrap_or(false);
+
+        if trigram_installed {
+            Self::Trigram
+        } else {
+            let searchable_items = items::table
+                .filter(category.eq_any(CATEGORY_SEARCHED))
+                .filter(image_id.ne(IMAGE_ID_EXCLUDED))
+                .load::<Item>(connection)
+                .unwrap()
+                .into_iter()
+                .map(|item| {
+                    let folded_name = fold(&item.name);
+                    (item, folded_name)
+                })
+                .collect();
+
+            Self::InProcess(searchable_items)
+        }
+    }
+
+    // Exact matches first, then prefixes, then by similarity. The bool is true when there's another page
+    pub fn search(
+        &self,
+        text: &str,
+        page: usize,
+        connection: &mut PgConnection,
+    ) -> (Vec<Item>, bool) {
+        let mut items = match self {
+            Self::Trigram => Self::search_trigram(text, page, connection),
+            Self::InProcess(searchable_items) => {
+                Self::search_in_process(searchable_items, text, page)
+            }
+        };
+
+        let has_next_page = items.len() > SEARCH_PAGE_SIZE;
+        items.truncate(SEARCH_PAGE_SIZE);
+
+        (items, has_next_page)
+    }
+
+    fn search_trigram(text: &str, page: usize, connection: &mut PgConnection) -> Vec<Item> {
+        use crate::database::functions::{f_unaccent, similarity, word_similarity, WordSimilar};
+        use crate::database::schema::items::{self, *};
+
+        let text = text.to_owned();
+        let pattern = escape_like(&text);
+
+        items::table
+            .filter(category.eq_any(CATEGORY_SEARCHED))
+            .filter(image_id.ne(IMAGE_ID_EXCLUDED))
+            .filter(
+                f_unaccent(name)
+                    .ilike(f_unaccent(format!("%{pattern}%")))
+                    .or(WordSimilar::new(f_unaccent(name), f_unaccent(text.clone()))),
+            )
+            .order((
+                f_unaccent(name).ilike(f_unaccent(pattern.clone())).desc(),
+                f_unaccent(name)
+                    .ilike(f_unaccent(format!("{pattern}%")))
+                    .desc(),
+                word_similarity(f_unaccent(text.clone()), f_unaccent(name)).desc(),
+                similarity(f_unaccent(text), f_unaccent(name)).desc(),
+                name.asc(),
+                id.asc(),
+            ))
+            .limit(SEARCH_PAGE_SIZE as i64 + 1)
+            .offset((page * SEARCH_PAGE_SIZE) as i64)
+            .load::<Item>(connection)
+            .unwrap()
+    }
+
+    fn search_in_process(
+        searchable_items: &[(Item, String)],
+        text: &str,
+        page: usize,
+    ) -> Vec<Item> {
+        let text = fold(text);
+        let text_trigrams = trigrams(&text);
+
+        searchable_items
+            .iter()
+            .filter_map(|(item, folded_name)| {
+                let score = SearchScore::new(&text, &text_trigrams, folded_name);
+                score.matches().then_some((score, item))
+            })
+            .sorted_by(|(left_score, left_item), (right_score, right_item)| {
+                right_score
+                    .cmp(left_score)
+                    .then_with(|| left_item.name.cmp(&right_item.name))
+                    .then_with(|| left_item.id.cmp(&right_item.id))
+            })
+            .skip(page * SEARCH_PAGE_SIZE)
+            .take(SEARCH_PAGE_SIZE + 1)
+            .map(|(_, item)| item.clone())
+            .collect()
+    }
+}
+
+// How well a folded name matches a folded search text, greater is better
+#[derive(PartialEq, Debug)]
+pub struct SearchScore {
+    exact: bool,
+    prefix: bool,
+    contains: bool,
+    word_similarity: f32,
+    similarity: f32,
+}
+
+impl SearchScore {
+    pub fn new(text: &str, text_trigrams: &HashSet<String>, folded_name: &str) -> Self {
+        let name_trigrams = trigrams(folded_name);
+        let common = text_trigrams.intersection(&name_trigrams).count() as f32;
+
+        let word_similarity = if text_trigrams.is_empty() {
+            0f32
+        } else {
+            common / text_trigrams.len() as f32
+        };
+
+        let all = (text_trigrams.len() + name_trigrams.len()) as f32 - common;
+        let similarity = if all == 0f32 { 0f32 } else { common / all };
+
+        Self {
+            exact: folded_name == text,
+            prefix: folded_name.starts_with(text),
+            contains: folded_name.contains(text),
+            word_similarity,
+            similarity,
+        }
+    }
+
+    pub fn matches(&self) -> bool {
+        self.contains || self.word_similarity >= WORD_SIMILARITY_THRESHOLD
+    }
+}
+
+impl PartialOrd for SearchScore {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Eq for SearchScore {}
+
+impl Ord for SearchScore {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.exact
+            .cmp(&other.exact)
+            .then(self.prefix.cmp(&other.prefix))
+            .then(self.word_similarity.total_cmp(&other.word_similarity))
+            .then(self.similarity.total_cmp(&other.similarity))
+    }
+}
+
+// Trigrams like pg_trgm: each word is padded with two spaces before and one after
+pub fn trigrams(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .flat_map(|word| {
+            let padded = format!("  {word} ").chars().collect_vec();
+            padded
+                .windows(3)
+                .map(|window| window.iter().collect::<String>())
+                .collect_vec()
+        })
+        .collect()
+}
+
+// ILIKE wildcards and its escape character are searched as themselves
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+// Lowercase and without accents, like f_unaccent with ILIKE
+pub fn fold(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .flat_map(|c| {
+            let folded = match c {
+                'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => "a",
+                'ç' => "c",
+                'è' | 'é' | 'ê' | 'ë' => "e",
+                'ì' | 'í' | 'î' | 'ï' => "i",
+                'ñ' => "n",
+                'ò' | 'ó' | 'ô' | 'õ' | 'ö' => "o",
+                'ù' | 'ú' | 'û' | 'ü' => "u",
+                'ý' | 'ÿ' => "y",
+                'æ' => "ae",
+                'œ' => "oe",
+                _ => "",
+            };
+
+            if folded.is_empty() {
+                vec![c]
+            } else {
+                folded.chars().collect()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use diesel::{connection::SimpleConnection, PgConnection};
+
+    use super::ItemSearch;
+    use crate::test_database::TestDatabase;
+
+    fn names(item_search: &ItemSearch, text: &str, connection: &mut PgConnection) -> Vec<String> {
+        let (items, has_next_page) = item_search.search(text, 0, connection);
+        assert!(!has_next_page);
+
+        items.into_iter().map(|item| item.name).collect()
+    }
+
+    fn check_ranking(item_search: &ItemSearch, connection: &mut PgConnection) {
+        // Exact match, then prefix, placeholder images are hidden
+        assert_eq!(
+            names(item_search, "ble", connection)[..2],
+            ["Blé", "Blé de sarrasin"]
+        );
+        assert!(!names(item_search, "ble", connection).contains(&"Blé doré".to_owned()));
+
+        assert_eq!(names(item_search, "GATEAU", connection), ["Gâteau"]);
+        // Shorter names are more similar
+        assert_eq!(
+            names(item_search, "bouftou", connection),
+            ["Laine de Bouftou", "Chapeau du Bouftou"]
+        );
+
+        // Only resources, equipments and consumables
+        assert!(names(item_search, "clef", connection).is_empty());
+
+        assert!(item_search.search("ble", 1, connection).0.is_empty());
+
+        // Wildcards don't match every name
+        assert!(names(item_search, "%", connection).is_empty());
+        assert!(names(item_search, "_", connection).is_empty());
+        assert!(names(item_search, "b%u", connection).is_empty());
+        assert!(names(item_search, "\\", connection).is_empty());
+    }
+
+    #[test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    fn search_ranks_in_database() {
+        let database = TestDatabase::new();
+        let connection = &mut database.connection();
+        let item_search = ItemSearch::new(connection);
+
+        if matches!(item_search, ItemSearch::Trigram) {
+            check_ranking(&item_search, connection);
+        }
+    }
+
+    #[test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    fn search_ranks_in_process() {
+        let database = TestDatabase::new();
+        let connection = &mut database.connection();
+        connection
+            .batch_execute("DROP EXTENSION IF EXISTS pg_trgm CASCADE")
+            .unwrap();
+
+        let item_search = ItemSearch::new(connection);
+
+        assert!(matches!(item_search, ItemSearch::InProcess(_)));
+        check_ranking(&item_search, connection);
+    }
+}
@@ -0,0 +1,49 @@
+use std::str::FromStr;
+
+// Stored as text in journal_entries
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum JournalTarget {
+    Inventory,
+    WishList,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum JournalReason {
+    Manual,
+    Craft,
+    Remove,
+    // Added during a farming session
+    Loot,
+}
+
+impl JournalTarget {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Inventory => "inventory",
+            Self::WishList => "wish_list",
+        }
+    }
+}
+
+impl FromStr for JournalTarget {
+    type Err = String;
+
+    fn from_str(target: &str) -> Result<Self, Self::Err> {
+        match target {
+            "inventory" => Ok(Self::Inventory),
+            "wish_list" => Ok(Self::WishList),
+            _ => Err(format!("Unknown journal target {target}")),
+        }
+    }
+}
+
+impl JournalReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Manual => "manual",
+            Self::Craft => "craft",
+            Self::Remove => "remove",
+            Self::Loot => "loot",
+        }
+    }
+}
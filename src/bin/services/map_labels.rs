@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use egui::Rect;
+
+use crate::database::models::{map::Map, sub_area::SubArea};
+
+// A name drawn on the world map
+pub struct MapLabel {
+    pub text: String,
+    // Center of its maps, in map coordinates
+    pub position: (f32, f32),
+    // Labels of bigger zones are placed first
+    pub maps_count: usize,
+}
+
+// Sub-area labels and area labels, biggest first
+pub fn map_labels(
+    sub_areas: &HashMap<SubArea, Vec<Map>>,
+    area_names: &HashMap<i32, String>,
+) -> (Vec<MapLabel>, Vec<MapLabel>) {
+    let centroid = |maps: &[&Map]| {
+        let count = maps.len() as f32;
+        (
+            maps.iter().map(|map| map.x as f32).sum::<f32>() / count,
+            maps.iter().map(|map| map.y as f32).sum::<f32>() / count,
+        )
+    };
+
+    let mut areas_maps: HashMap<i32, Vec<&Map>> = HashMap::new();
+    let mut sub_area_labels = sub_areas
+        .iter()
+        .filter(|(_, maps)| !maps.is_empty())
+        .map(|(sub_area, maps)| {
+            areas_maps
+                .entry(sub_area.area_id)
+                .or_default()
+                .extend(maps.iter());
+
+            MapLabel {
+                text: sub_area.name.clone(),
+                position: centroid(&maps.iter().collect::<Vec<_>>()),
+                maps_count: maps.len(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut area_labels = areas_maps
+        .into_iter()
+        .filter_map(|(area_id, maps)| {
+            area_names.get(&area_id).map(|name| MapLabel {
+                text: name.clone(),
+                position: centroid(&maps),
+                maps_count: maps.len(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    [&mut sub_area_labels, &mut area_labels]
+        .into_iter()
+        .for_each(|labels| {
+            labels.sort_by(|left, right| {
+                right
+                    .maps_count
+                    .cmp(&left.maps_count)
+                    .then_with(|| left.text.cmp(&right.text))
+            })
+        });
+
+    (sub_area_labels, area_labels)
+}
+
+// Indices of the rects kept, each one is dropped when it overlaps one kept before it
+pub fn place_labels(rects: &[Rect]) -> Vec<usize> {
+    let mut placed: Vec<Rect> = Vec::new();
+
+    rects
+        .iter()
+        .enumerate()
+        .filter_map(|(index, rect)| {
+            if placed
+                .iter()
+                .any(|placed_rect| placed_rect.intersects(*rect))
+            {
+                None
+            } else {
+                placed.push(*rect);
+                Some(index)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use egui::{Pos2, Rect, Vec2};
+
+    use super::{map_labels, place_labels};
+    use crate::database::models::{map::Map, sub_area::SubArea};
+
+    #[test]
+    fn labels_are_centered_on_their_maps() {
+        let fields = SubArea::new(1, "Champs".to_owned(), 1);
+        let plains = SubArea::new(2, "Plaines".to_owned(), 1);
+        let sub_areas = HashMap::from([
+            (
+                fields,
+                vec![
+                    Map::new(1, None, 0, 0, 1),
+                    Map::new(2, None, 2, 0, 1),
+                    Map::new(3, None, 1, 3, 1),
+                ],
+            ),
+            (plains, vec![Map::new(4, None, 5, 5, 2)]),
+        ]);
+        let area_names = HashMap::from([(1, "Astrub".to_owned())]);
+
+        let (sub_area_labels, area_labels) = map_labels(&sub_areas, &area_names);
+
+        assert_eq!(sub_area_labels[0].text, "Champs");
+        assert_eq!(sub_area_labels[0].position, (1f32, 1f32));
+        assert_eq!(sub_area_labels[1].position, (5f32, 5f32));
+        assert_eq!(area_labels.len(), 1);
+        assert_eq!(area_labels[0].position, (2f32, 2f32));
+        assert_eq!(area_labels[0].maps_count, 4);
+    }
+
+    #[test]
+    fn overlapping_labels_are_dropped() {
+        let rect = |x: f32, y: f32| Rect::from_min_size(Pos2::new(x, y), Vec2::new(50f32, 10f32));
+
+        let rects = [
+            rect(0f32, 0f32),
+            rect(40f32, 5f32),
+            rect(60f32, 0f32),
+            rect(0f32, 20f32),
+        ];
+
+        assert_eq!(place_labels(&rects), vec![0, 2, 3]);
+    }
+}
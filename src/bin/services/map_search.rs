@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use crate::database::models::sub_area::SubArea;
+
+use super::item_search::fold;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum GoToTarget<'a> {
+    Coordinates(i16, i16),
+    SubArea(&'a SubArea),
+    // Area id
+    Area(i32),
+}
+
+// "[-5, 3]", "-5,3" or "-5 3"
+pub fn parse_coordinates(text: &str) -> Option<(i16, i16)> {
+    let text = text.trim();
+    let text = text
+        .strip_prefix('[')
+        .and_then(|text| text.strip_suffix(']'))
+        .unwrap_or(text);
+
+    let mut coordinates = text
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse::<i16>());
+
+    match (coordinates.next(), coordinates.next(), coordinates.next()) {
+        (Some(Ok(x)), Some(Ok(y)), None) => Some((x, y)),
+        _ => None,
+    }
+}
+
+// Coordinates, or the sub-area or area whose name matches best without accents: exact names
+// first, then prefixes, then anywhere in the name. Sub-areas win over areas matching as well
+pub fn find_target<'a>(
+    text: &str,
+    sub_areas: impl IntoIterator<Item = &'a SubArea>,
+    area_names: &HashMap<i32, String>,
+) -> Option<GoToTarget<'a>> {
+    if let Some((x, y)) = parse_coordinates(text) {
+        return Some(GoToTarget::Coordinates(x, y));
+    }
+
+    let text = fold(text.trim());
+    if text.is_empty() {
+        return None;
+    }
+
+    let rank = |name: &str| {
+        let name = fold(name);
+        if name == text {
+            Some(0)
+        } else if name.starts_with(&text) {
+            Some(1)
+        } else if name.contains(&text) {
+            Some(2)
+        } else {
+            None
+        }
+    };
+
+    let sub_area = sub_areas
+        .into_iter()
+        .filter_map(|sub_area| {
+            rank(&sub_area.name).map(|rank| ((rank, 0, sub_area.name.len(), sub_area.id), sub_area))
+        })
+        .min_by_key(|(key, _)| *key)
+        .map(|(key, sub_area)| (key, GoToTarget::SubArea(sub_area)));
+
+    let area = area_names
+        .iter()
+        .filter_map(|(id, name)| rank(name).map(|rank| ((rank, 1, name.len(), *id), *id)))
+        .min_by_key(|(key, _)| *key)
+        .map(|(key, id)| (key, GoToTarget::Area(id)));
+
+    sub_area
+        .into_iter()
+        .chain(area)
+        .min_by_key(|(key, _)| *key)
+        .map(|(_, target)| target)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{find_target, parse_coordinates, GoToTarget};
+    use crate::database::models::sub_area::SubArea;
+
+    #[test]
+    fn coordinates_are_parsed_with_or_without_brackets() {
+        assert_eq!(parse_coordinates("[-5, 3]"), Some((-5, 3)));
+        assert_eq!(parse_coordinates(" 4,-12 "), Some((4, -12)));
+        assert_eq!(parse_coordinates("4 -12"), Some((4, -12)));
+        assert_eq!(parse_coordinates("[4]"), None);
+        assert_eq!(parse_coordinates("4, 5, 6"), None);
+        assert_eq!(parse_coordinates("Astrub"), None);
+    }
+
+    #[test]
+    fn names_are_matched_without_accents() {
+        let fields = SubArea::new(1, "Champs d'Astrub".to_owned(), 1);
+        let forest = SubArea::new(2, "Forêt d'Astrub".to_owned(), 1);
+        let sub_areas = [fields, forest.clone()];
+        let area_names = HashMap::from([(1, "Astrub".to_owned()), (2, "Amakna".to_owned())]);
+
+        assert_eq!(
+            find_target("foret", &sub_areas, &area_names),
+            Some(GoToTarget::SubArea(&forest))
+        );
+        // The exact area name before sub-areas only containing it
+        assert_eq!(
+            find_target("ASTRUB", &sub_areas, &area_names),
+            Some(GoToTarget::Area(1))
+        );
+        assert_eq!(
+            find_target("[1, 2]", &sub_areas, &area_names),
+            Some(GoToTarget::Coordinates(1, 2))
+        );
+        assert_eq!(find_target("Bonta", &sub_areas, &area_names), None);
+    }
+}
@@ -0,0 +1,10 @@
+pub mod craft;
+pub mod database_worker;
+pub mod drop_rates;
+pub mod farming_route;
+pub mod heatmap;
+pub mod item_search;
+pub mod journal;
+pub mod map_labels;
+pub mod map_search;
+pub mod price_import;
@@ -0,0 +1,215 @@
+use std::{fs, path::Path};
+
+use chrono::{Local, NaiveDate};
+use diesel::{insert_into, prelude::*, PgConnection};
+
+use crate::{
+    crafting::cost::LOT_SIZES,
+    database::models::{
+        item::Item,
+        price::{Kamas, Price},
+    },
+};
+
+// Item id or exact name, then lot size, unit price and an optional date (YYYY-MM-DD).
+// Separated by commas or semicolons, a first line that doesn't parse is taken as a header
+#[derive(PartialEq, Debug)]
+struct PriceLine {
+    item: String,
+    lot_size: i16,
+    unit_price: Kamas,
+    price_date: Option<NaiveDate>,
+}
+
+impl PriceLine {
+    fn parse(line: &str) -> Result<Self, String> {
+        let separator = if line.contains(';') { ';' } else { ',' };
+        let columns = line.split(separator).map(str::trim).collect::<Vec<_>>();
+
+        let [item, lot_size, unit_price, rest @ ..] = columns.as_slice() else {
+            return Err(format!(
+                "Expected item, lot size and unit price in \"{line}\""
+            ));
+        };
+
+        let lot_size = lot_size
+            .parse::<i16>()
+            .ok()
+            .filter(|lot_size| LOT_SIZES.contains(lot_size))
+            .ok_or_else(|| format!("Lot size must be 1, 10 or 100 in \"{line}\""))?;
+        let unit_price = unit_price
+            .parse::<Kamas>()
+            .ok()
+            .filter(|unit_price| *unit_price >= 0)
+            .ok_or_else(|| format!("Invalid unit price in \"{line}\""))?;
+        let price_date = match rest.first().filter(|date| !date.is_empty()) {
+            Some(date) => Some(
+                NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                    .map_err(|_| format!("Invalid date in \"{line}\""))?,
+            ),
+            None => None,
+        };
+
+        Ok(Self {
+            item: item.to_string(),
+            lot_size,
+            unit_price,
+            price_date,
+        })
+    }
+}
+
+// Number of prices imported and one message per line that failed
+pub fn import_prices(path: &Path, connection: &mut PgConnection) -> (usize, Vec<String>) {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(error) => return (0, vec![format!("Can't read {}: {error}", path.display())]),
+    };
+
+    let mut errors = Vec::new();
+    let today = Local::now().date_naive();
+
+    let prices = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter_map(|(index, line)| {
+            let price_line = match PriceLine::parse(line) {
+                Ok(price_line) => price_line,
+                Err(_) if index == 0 => return None,
+                Err(error) => {
+                    errors.push(format!("Line {}: {error}", index + 1));
+                    return None;
+                }
+            };
+
+            match find_item(&price_line.item, connection) {
+                Some(item) => Some(Price::new(
+                    item.id,
+                    price_line.lot_size,
+                    price_line.unit_price,
+                    price_line.price_date.unwrap_or(today),
+                )),
+                None => {
+                    errors.push(format!(
+                        "Line {}: unknown item {}",
+                        index + 1,
+                        price_line.item
+                    ));
+                    None
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let result = connection.transaction(|connection| {
+        prices
+            .iter()
+            .try_for_each(|price| save_price(price, connection))
+    });
+
+    match result {
+        Ok(()) => (prices.len(), errors),
+        Err(error) => {
+            errors.push(format!("Nothing imported: {error}"));
+            (0, errors)
+        }
+    }
+}
+
+pub fn save_price(price: &Price, connection: &mut PgConnection) -> QueryResult<()> {
+    use crate::database::schema::prices;
+
+    insert_into(prices::table)
+        .values(price)
+        .on_conflict((prices::item_id, prices::lot_size, prices::price_date))
+        .do_update()
+        .set(prices::unit_price.eq(price.unit_price))
+        .execute(connection)
+        .map(|_| ())
+}
+
+fn find_item(item: &str, connection: &mut PgConnection) -> Option<Item> {
+    use crate::database::schema::items;
+
+    if let Ok(id) = item.parse::<i32>() {
+        return items::table.find(id).first(connection).ok();
+    }
+
+    items::table
+        .filter(items::name.eq(item))
+        .order(items::id)
+        .first(connection)
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use chrono::NaiveDate;
+    use diesel::prelude::*;
+
+    use super::{import_prices, PriceLine};
+    use crate::{
+        crafting::cost::PriceList, database::models::item::Item, database::schema::items,
+        test_database::TestDatabase,
+    };
+
+    #[test]
+    fn parses_both_separators_and_optional_date() {
+        assert_eq!(
+            PriceLine::parse("Blé, 10, 25"),
+            Ok(PriceLine {
+                item: "Blé".to_owned(),
+                lot_size: 10,
+                unit_price: 25,
+                price_date: None,
+            })
+        );
+        assert_eq!(
+            PriceLine::parse("289;100;3;2026-10-01"),
+            Ok(PriceLine {
+                item: "289".to_owned(),
+                lot_size: 100,
+                unit_price: 3,
+                price_date: NaiveDate::from_ymd_opt(2026, 10, 1),
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_lot_sizes_and_headers() {
+        assert!(PriceLine::parse("Blé,5,25").is_err());
+        assert!(PriceLine::parse("item,lot_size,unit_price,date").is_err());
+        assert!(PriceLine::parse("Blé,10").is_err());
+    }
+
+    #[test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    fn imports_known_items_and_reports_the_others() {
+        let database = TestDatabase::new();
+        let connection = &mut database.connection();
+
+        let path = env::temp_dir().join(format!("dofus_prices_{}.csv", process::id()));
+        fs::write(
+            &path,
+            "item;lot_size;unit_price;date\nBlé;1;12\n2;10;4;2026-10-01\nInconnu;1;5\nEau;7;5\n",
+        )
+        .unwrap();
+
+        let (imported, errors) = import_prices(&path, connection);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(imported, 2);
+        assert_eq!(errors.len(), 2);
+
+        let prices = PriceList::load(connection);
+        let wheat: Item = items::table.find(1).first(connection).unwrap();
+        let water: Item = items::table.find(2).first(connection).unwrap();
+
+        assert_eq!(prices.unit_price(&wheat, 1), Some(12));
+        assert_eq!(prices.unit_price(&water, 10), Some(4));
+        assert_eq!(prices.buy_cost(&water, 5), Some(40));
+    }
+}
@@ -0,0 +1,69 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    crafting::recipe_graph::RecipeGraph,
+    database::models::{
+        item::{Item, ItemList, Quantity},
+        recipe::Recipe,
+    },
+};
+
+// The recipes of fixtures.sql without a database: Farine: 3 Blé. Pain: 2 Farine, 1 Eau.
+// Gâteau: 1 Pain, 1 Farine
+pub struct Bakery {
+    pub graph: RecipeGraph,
+    pub wheat: Item,
+    pub water: Item,
+    pub flour: Item,
+    pub bread: Item,
+    pub cake: Item,
+}
+
+pub fn bakery() -> Bakery {
+    let wheat = Item::new(1, "Blé".to_owned(), 0, 1001);
+    let water = Item::new(2, "Eau".to_owned(), 0, 1002);
+    let flour = Item::new(3, "Farine".to_owned(), 0, 1003);
+    let bread = Item::new(4, "Pain".to_owned(), 2, 1004);
+    let cake = Item::new(5, "Gâteau".to_owned(), 2, 1005);
+
+    let graph = graph(&[
+        (&flour, &wheat, 3),
+        (&bread, &flour, 2),
+        (&bread, &water, 1),
+        (&cake, &bread, 1),
+        (&cake, &flour, 1),
+    ]);
+
+    Bakery {
+        graph,
+        wheat,
+        water,
+        flour,
+        bread,
+        cake,
+    }
+}
+
+// (result, ingredient, quantity)
+pub fn graph(recipes: &[(&Item, &Item, Quantity)]) -> RecipeGraph {
+    RecipeGraph::from_recipes(
+        recipes
+            .iter()
+            .map(|(result, ingredient, quantity)| {
+                (
+                    (*result).clone(),
+                    Recipe::new(result.id, ingredient.id, *quantity),
+                    (*ingredient).clone(),
+                )
+            })
+            .collect(),
+    )
+}
+
+pub fn list(items: &[(&Item, Quantity)]) -> ItemList {
+    ItemList::with_items(BTreeMap::from_iter(
+        items
+            .iter()
+            .map(|(item, quantity)| ((*item).clone(), *quantity)),
+    ))
+}
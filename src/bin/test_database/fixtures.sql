@@ -0,0 +1,32 @@
+-- Small catalog used by the tests, categories: 0 resource, 1 equipment, 2 consumable
+INSERT INTO items (id, name, category, image_id) VALUES
+  (1, 'Blé', 0, 1001),
+  (2, 'Eau', 0, 1002),
+  (3, 'Farine', 0, 1003),
+  (4, 'Pain', 2, 1004),
+  (5, 'Gâteau', 2, 1005),
+  (6, 'Œuf', 0, 1006),
+  (7, 'Pierre d''âme', 0, 1007),
+  (8, 'Fragment d''âme', 0, 1008),
+  (9, 'Chapeau du Bouftou', 1, 1009),
+  (10, 'Laine de Bouftou', 0, 1010),
+  (11, 'Blé doré', 0, 89042),
+  (12, 'Blé de sarrasin', 0, 1012),
+  (13, 'Clef du donjon', 3, 1013);
+
+INSERT INTO recipes (result_item_id, ingredient_item_id, quantity) VALUES
+  -- Farine: 3 Blé
+  (3, 1, 3),
+  -- Pain: 2 Farine, 1 Eau
+  (4, 3, 2),
+  (4, 2, 1),
+  -- Gâteau: 1 Pain, 1 Farine, 2 Œuf
+  (5, 4, 1),
+  (5, 3, 1),
+  (5, 6, 2),
+  -- Pierre d'âme and Fragment d'âme need each other
+  (7, 8, 1),
+  (8, 7, 2),
+  -- Chapeau du Bouftou: 5 Laine de Bouftou, 1 Blé
+  (9, 10, 5),
+  (9, 1, 1);
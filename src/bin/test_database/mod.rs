@@ -0,0 +1,128 @@
+use std::{
+    env, process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use diesel::{
+    connection::SimpleConnection,
+    prelude::*,
+    r2d2::{ConnectionManager, Pool, PooledConnection},
+    sql_query, PgConnection,
+};
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+use tracing::warn;
+
+use crate::database::{models::item::Item, schema::items};
+
+pub mod bakery;
+
+const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+
+// Same functions as in SETUP.md, the migrations need them
+const UNACCENT_SETUP: &str = r#"
+CREATE EXTENSION IF NOT EXISTS unaccent;
+
+CREATE OR REPLACE FUNCTION public.immutable_unaccent(regdictionary, text)
+  RETURNS text
+  LANGUAGE c IMMUTABLE PARALLEL SAFE STRICT AS '$libdir/unaccent', 'unaccent_dict';
+
+CREATE OR REPLACE FUNCTION public.f_unaccent(text)
+  RETURNS text
+  LANGUAGE sql IMMUTABLE PARALLEL SAFE STRICT
+  BEGIN ATOMIC
+SELECT public.immutable_unaccent(regdictionary 'public.unaccent', $1);
+END;
+"#;
+
+static DATABASE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+// A database created for one test with the migrations and the fixture catalog, dropped at the end.
+// TEST_DATABASE_URL must point to a database of a user allowed to create databases and extensions
+pub struct TestDatabase {
+    admin_url: String,
+    name: String,
+    pool: Pool<ConnectionManager<PgConnection>>,
+}
+
+impl TestDatabase {
+    // The database tests are ignored by default, run them with --ignored once TEST_DATABASE_URL is set
+    pub fn new() -> Self {
+        dotenvy::dotenv().ok();
+
+        let admin_url = env::var("TEST_DATABASE_URL")
+            .expect("TEST_DATABASE_URL must be set to run the database tests, see SETUP.md");
+
+        let name = format!(
+            "dofus_test_{}_{}",
+            process::id(),
+            DATABASE_COUNT.fetch_add(1, Ordering::SeqCst)
+        );
+
+        let mut admin_connection =
+            PgConnection::establish(&admin_url).expect("Failed to connect to TEST_DATABASE_URL");
+        sql_query(format!("DROP DATABASE IF EXISTS {name}"))
+            .execute(&mut admin_connection)
+            .unwrap();
+        sql_query(format!("CREATE DATABASE {name}"))
+            .execute(&mut admin_connection)
+            .unwrap();
+
+        let (server_url, _) = admin_url
+            .rsplit_once('/')
+            .expect("TEST_DATABASE_URL must end with a database name");
+        let manager = ConnectionManager::<PgConnection>::new(format!("{server_url}/{name}"));
+        let pool = Pool::builder().max_size(2).build(manager).unwrap();
+
+        let test_database = Self {
+            admin_url,
+            name,
+            pool,
+        };
+
+        let mut connection = test_database.connection();
+        connection.batch_execute(UNACCENT_SETUP).unwrap();
+
+        // Optional like in SETUP.md, the item search works without it
+        if connection
+            .batch_execute("CREATE EXTENSION IF NOT EXISTS pg_trgm")
+            .is_err()
+        {
+            warn!("pg_trgm is not available, the item search will be done in process");
+        }
+
+        connection.run_pending_migrations(MIGRATIONS).unwrap();
+        connection
+            .batch_execute(include_str!("fixtures.sql"))
+            .unwrap();
+        drop(connection);
+
+        test_database
+    }
+
+    pub fn connection(&self) -> PooledConnection<ConnectionManager<PgConnection>> {
+        self.pool.get().unwrap()
+    }
+}
+
+impl Drop for TestDatabase {
+    fn drop(&mut self) {
+        let Ok(mut admin_connection) = PgConnection::establish(&self.admin_url) else {
+            return;
+        };
+
+        // Connections can still be open in the pool or in a worker
+        if let Err(error) = sql_query(format!(
+            "DROP DATABASE IF EXISTS {} WITH (FORCE)",
+            self.name
+        ))
+        .execute(&mut admin_connection)
+        {
+            warn!("Failed to drop test database {}: {error}", self.name);
+        }
+    }
+}
+
+// An item of fixtures.sql
+pub fn item(connection: &mut PgConnection, id: i32) -> Item {
+    items::table.find(id).first(connection).unwrap()
+}
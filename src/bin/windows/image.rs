@@ -1,25 +1,23 @@
-use std::{env, path::Path};
+use std::{collections::HashMap, env, hash::Hash, path::Path};
 
 use egui::{ColorImage, Context, TextureHandle};
-use image::ImageReader;
+use image::{ImageError, ImageReader};
 use lombok::AllArgsConstructor;
 use tracing::trace_span;
 
 #[derive(AllArgsConstructor, Clone)]
 pub struct Image {
     pub handle: TextureHandle,
-    pub used: bool,
 }
 
 impl Image {
-    pub fn from_path(ctx: &Context, path: &Path) -> Self {
+    // Fails when the file is missing or can't be decoded
+    pub fn from_path(ctx: &Context, path: &Path) -> Result<Self, ImageError> {
         let real_path = Path::new(&env::var("DOFUS_RESOURCES").unwrap())
             .join(path)
-            .canonicalize()
-            .unwrap();
+            .canonicalize()?;
 
-        // Images start at 1
-        let color_image = Self::load_image_from_path(&real_path);
+        let color_image = Self::load_image_from_path(&real_path)?;
         let handle = ctx.load_texture(
             real_path
                 .file_name()
@@ -30,36 +28,229 @@ impl Image {
             Default::default(),
         );
 
-        Image::new(handle, true)
+        Ok(Image::new(handle))
     }
 
-    pub fn map_from_ui_and_index(ctx: &Context, index: u16, zoom: f32) -> Self {
+    pub fn map_from_ui_and_index(ctx: &Context, index: u16, zoom: f32) -> Result<Self, ImageError> {
         // Images start at 1
         let path = format!("images/worldmap/{}/{}.jpg", zoom, index + 1);
         let path = Path::new(&path);
-        Self::from_path(ctx, &path)
+        Self::from_path(ctx, path)
     }
 
-    pub fn item_from_image_id(ctx: &Context, id: i32) -> Self {
+    pub fn item_from_image_id(ctx: &Context, id: i32) -> Result<Self, ImageError> {
         let path = format!("images/items/{id}.png");
         let path = Path::new(&path);
-        Self::from_path(ctx, &path)
+        Self::from_path(ctx, path)
     }
 
-    pub fn monster_from_id(ctx: &Context, id: i32) -> Self {
+    pub fn monster_from_id(ctx: &Context, id: i32) -> Result<Self, ImageError> {
         let path = format!("images/monsters/{id}.png");
         let path = Path::new(&path);
-        Self::from_path(ctx, &path)
+        Self::from_path(ctx, path)
+    }
+
+    // Size of the texture in memory, in bytes
+    pub fn byte_size(&self) -> usize {
+        let [width, height] = self.handle.size();
+        width * height * 4
     }
 
-    fn load_image_from_path(path: &Path) -> ColorImage {
+    fn load_image_from_path(path: &Path) -> Result<ColorImage, ImageError> {
         let span = trace_span!("draw_map_body_loop");
         let _guard = span.enter();
 
-        let image = ImageReader::open(path).unwrap().decode().unwrap();
+        let image = ImageReader::open(path)?.decode()?;
         let size = [image.width() as _, image.height() as _];
         let image_buffer = image.to_rgba8();
         let pixels = image_buffer.as_flat_samples();
-        ColorImage::from_rgba_unmultiplied(size, pixels.as_slice())
+        Ok(ColorImage::from_rgba_unmultiplied(size, pixels.as_slice()))
+    }
+}
+
+enum TileStatus<V> {
+    Loading,
+    Ready(V),
+    // Not loaded again, a missing tile would be requested every frame
+    Failed,
+}
+
+struct Tile<V> {
+    status: TileStatus<V>,
+    bytes: usize,
+    // Frame of the last use
+    last_used: u64,
+}
+
+// Loaded values kept until the budget is exceeded, then the least recently used ones are dropped.
+// Values used during the current frame are never dropped
+pub struct TileCache<K, V> {
+    tiles: HashMap<K, Tile<V>>,
+    budget: usize,
+    used: usize,
+    frame: u64,
+}
+
+impl<K: Hash + Eq + Copy, V> TileCache<K, V> {
+    pub fn new(budget: usize) -> Self {
+        Self {
+            tiles: HashMap::new(),
+            budget,
+            used: 0,
+            frame: 0,
+        }
+    }
+
+    pub fn next_frame(&mut self) {
+        self.frame += 1;
+    }
+
+    // Loading, loaded or failed
+    pub fn is_requested(&self, key: &K) -> bool {
+        self.tiles.contains_key(key)
+    }
+
+    pub fn is_loading(&self, key: &K) -> bool {
+        self.tiles
+            .get(key)
+            .is_some_and(|tile| matches!(tile.status, TileStatus::Loading))
+    }
+
+    pub fn loading_count(&self) -> usize {
+        self.tiles
+            .values()
+            .filter(|tile| matches!(tile.status, TileStatus::Loading))
+            .count()
+    }
+
+    pub fn set_loading(&mut self, key: K) {
+        self.set_status(key, TileStatus::Loading);
+    }
+
+    pub fn set_failed(&mut self, key: K) {
+        self.set_status(key, TileStatus::Failed);
+    }
+
+    fn set_status(&mut self, key: K, status: TileStatus<V>) {
+        if let Some(tile) = self.tiles.insert(
+            key,
+            Tile {
+                status,
+                bytes: 0,
+                last_used: self.frame,
+            },
+        ) {
+            self.used -= tile.bytes;
+        }
+    }
+
+    // The value is then used in this frame
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let frame = self.frame;
+        let tile = self.tiles.get_mut(key)?;
+
+        match &tile.status {
+            TileStatus::Ready(value) => {
+                tile.last_used = frame;
+                Some(value)
+            }
+            TileStatus::Loading | TileStatus::Failed => None,
+        }
+    }
+
+    pub fn insert(&mut self, key: K, value: V, bytes: usize) {
+        if let Some(tile) = self.tiles.insert(
+            key,
+            Tile {
+                status: TileStatus::Ready(value),
+                bytes,
+                last_used: self.frame,
+            },
+        ) {
+            self.used -= tile.bytes;
+        }
+        self.used += bytes;
+
+        self.evict();
+    }
+
+    fn evict(&mut self) {
+        while self.used > self.budget {
+            let oldest = self
+                .tiles
+                .iter()
+                .filter(|(_, tile)| {
+                    matches!(tile.status, TileStatus::Ready(_)) && tile.last_used < self.frame
+                })
+                .min_by_key(|(_, tile)| tile.last_used)
+                .map(|(key, _)| *key);
+
+            let Some(oldest) = oldest else {
+                break;
+            };
+
+            if let Some(tile) = self.tiles.remove(&oldest) {
+                self.used -= tile.bytes;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TileCache;
+
+    #[test]
+    fn least_recently_used_tiles_are_dropped_over_budget() {
+        let mut cache = TileCache::new(20);
+
+        cache.insert(1, "a", 10);
+        cache.next_frame();
+        cache.insert(2, "b", 10);
+        cache.next_frame();
+        assert_eq!(cache.get(&1), Some(&"a"));
+
+        cache.insert(3, "c", 10);
+
+        // 2 was used before 1
+        assert!(!cache.is_requested(&2));
+        assert_eq!(cache.get(&1), Some(&"a"));
+        assert_eq!(cache.get(&3), Some(&"c"));
+    }
+
+    #[test]
+    fn tiles_of_the_current_frame_are_kept() {
+        let mut cache = TileCache::new(10);
+
+        cache.set_loading(1);
+        assert_eq!(cache.get(&1), None::<&&str>);
+        assert_eq!(cache.loading_count(), 1);
+
+        cache.insert(1, "a", 10);
+        cache.insert(2, "b", 10);
+
+        // Over budget until the next frame needs room
+        assert!(cache.is_requested(&1));
+        assert_eq!(cache.loading_count(), 0);
+
+        cache.next_frame();
+        cache.insert(3, "c", 10);
+
+        assert!(!cache.is_requested(&1));
+        assert!(!cache.is_requested(&2));
+        assert!(cache.is_requested(&3));
+    }
+
+    #[test]
+    fn failed_tiles_are_not_loading_nor_requested_again() {
+        let mut cache = TileCache::new(10);
+
+        cache.set_loading(1);
+        cache.set_failed(1);
+
+        assert_eq!(cache.get(&1), None::<&&str>);
+        assert!(cache.is_requested(&1));
+        assert!(!cache.is_loading(&1));
+        assert_eq!(cache.loading_count(), 0);
     }
 }
@@ -1,25 +1,47 @@
-use std::{collections::HashMap, sync::mpsc::Sender};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::mpsc::Sender,
+};
 
 use diesel::{
     r2d2::{ConnectionManager, Pool},
     PgConnection,
 };
-use egui::{load::SizedTexture, Context, Vec2, Window};
+use egui::{load::SizedTexture, Context, Ui, Vec2, Window};
 use egui_dock::{DockArea, DockState, NodeIndex, Style};
+use itertools::Itertools;
 use tracing::trace_span;
 
-use crate::database::models::{
-    item::{Item, ItemList},
-    monster::Monster,
-    sub_area::SubArea,
+use crate::{
+    crafting::{
+        cost::{format_kamas, CraftCost, PriceList},
+        planner::CraftPlan,
+        recipe_graph::RecipeGraph,
+    },
+    database::models::{
+        item::{Item, ItemList, Quantity},
+        journal_entry::JournalEntry,
+        monster::Monster,
+        project::Project,
+        sub_area::SubArea,
+    },
+    services::{database_worker::DatabaseWorker, drop_rates::DropRates},
 };
 
 use super::{
     image::Image,
     main_window::{AsyncStatus, ItemsRelations, MainWindow},
     tabs::items_tabs::{
-        item_tab_viewer::ItemTabsViewer, item_tabs_data::ItemTabsData, monsters_tab::MonstersTab,
-        resources_tab::ResourcesTab, search_item_tabs::SearchItemTab, wish_list_tab::WishListTab,
+        farming_tab::{ActiveSession, FarmingAction, FarmingTab},
+        history_tab::{HistoryAction, HistoryTab},
+        item_tab_viewer::ItemTabsViewer,
+        item_tabs_data::ItemTabsData,
+        monsters_tab::MonstersTab,
+        prices_tab::PricesTab,
+        resources_tab::ResourcesTab,
+        search_item_tabs::SearchItemTab,
+        uses_tab::UsesTab,
+        wish_list_tab::{ProjectAction, WishListTab},
     },
 };
 
@@ -30,16 +52,29 @@ pub struct ItemsWindow {
 impl ItemsWindow {
     pub fn new(
         pool: Pool<ConnectionManager<PgConnection>>,
-        item_clicked_tx: Sender<(Item, i16)>,
-        new_ingredient_tx: Sender<(Item, isize)>,
-        remove_item_tx: Sender<(Item, i16, bool)>,
+        item_clicked_tx: Sender<(Item, Quantity)>,
+        new_ingredient_tx: Sender<(Item, Quantity)>,
+        remove_item_tx: Sender<(Item, Quantity, bool)>,
+        history_action_tx: Sender<HistoryAction>,
+        database_worker: DatabaseWorker,
+        prices_tx: Sender<PriceList>,
+        project_action_tx: Sender<ProjectAction>,
+        farming_action_tx: Sender<FarmingAction>,
     ) -> Self {
-        let wish_list_tab = WishListTab::new(remove_item_tx);
+        let wish_list_tab = WishListTab::new(remove_item_tx, project_action_tx);
+        let farming_tab = FarmingTab::new(farming_action_tx, new_ingredient_tx.clone());
         let resources_tab = ResourcesTab::new(new_ingredient_tx);
         let search_item_tab = SearchItemTab::new(pool, item_clicked_tx);
         let monsters_tab = MonstersTab::new();
+        let history_tab = HistoryTab::new(history_action_tx);
+        let prices_tab = PricesTab::new(database_worker, prices_tx);
+        let uses_tab = UsesTab::new();
 
-        let mut dock_state = DockState::new(vec![ItemTabsData::SearchItem(search_item_tab)]);
+        let mut dock_state = DockState::new(vec![
+            ItemTabsData::SearchItem(search_item_tab),
+            ItemTabsData::History(history_tab),
+            ItemTabsData::Uses(uses_tab),
+        ]);
         let surface = dock_state.main_surface_mut();
         let tabs = surface.split_right(
             NodeIndex::root(),
@@ -47,8 +82,22 @@ impl ItemsWindow {
             vec![ItemTabsData::Resources(resources_tab)],
         );
 
-        surface.split_right(tabs[1], 0.5, vec![ItemTabsData::Monsters(monsters_tab)]);
-        surface.split_below(tabs[0], 0.5, vec![ItemTabsData::WishList(wish_list_tab)]);
+        surface.split_right(
+            tabs[1],
+            0.5,
+            vec![
+                ItemTabsData::Monsters(monsters_tab),
+                ItemTabsData::Farming(farming_tab),
+            ],
+        );
+        surface.split_below(
+            tabs[0],
+            0.5,
+            vec![
+                ItemTabsData::WishList(wish_list_tab),
+                ItemTabsData::Prices(prices_tab),
+            ],
+        );
 
         Self { dock_state }
     }
@@ -57,11 +106,22 @@ impl ItemsWindow {
         &mut self,
         ctx: &Context,
         items: &ItemsRelations,
+        visible_items: &ItemsRelations,
         ingredients_quantity: &ItemList,
         calculated_inventory: &ItemList,
+        craft_plan: &CraftPlan,
+        craft_cost: &CraftCost,
+        prices: &PriceList,
         items_images: &HashMap<Item, AsyncStatus<Image>>,
         monsters_images: &HashMap<Monster, AsyncStatus<Image>>,
-        current_sub_area: &Option<SubArea>,
+        current_sub_areas: &HashSet<SubArea>,
+        recipe_graph: &RecipeGraph,
+        history: &[(JournalEntry, Item)],
+        projects: &[Project],
+        items_project: &HashMap<Item, i32>,
+        projects_progress: &HashMap<i32, f32>,
+        active_session: &Option<ActiveSession>,
+        drop_rates: &DropRates,
     ) {
         Window::new("Items")
             .default_size(Vec2::new(1500f32, 1000f32))
@@ -72,11 +132,22 @@ impl ItemsWindow {
 
                 let mut tab_viewer = ItemTabsViewer::new(
                     items,
+                    visible_items,
                     ingredients_quantity,
                     calculated_inventory,
+                    craft_plan,
+                    craft_cost,
+                    prices,
                     items_images,
                     monsters_images,
-                    current_sub_area,
+                    current_sub_areas,
+                    recipe_graph,
+                    history,
+                    projects,
+                    items_project,
+                    projects_progress,
+                    active_session,
+                    drop_rates,
                 );
 
                 DockArea::new(&mut self.dock_state)
@@ -86,6 +157,30 @@ impl ItemsWindow {
             });
     }
 
+    // Total cost of the missing ingredients, shown in the wish list and resources tabs
+    pub(crate) fn missing_cost_ui(ui: &mut Ui, craft_cost: &CraftCost) {
+        let unpriced = if craft_cost.unpriced_missing.is_empty() {
+            String::new()
+        } else {
+            format!(
+                " ({} items without price)",
+                craft_cost.unpriced_missing.len()
+            )
+        };
+
+        ui.label(format!(
+            "Missing ingredients cost: {}{unpriced}",
+            format_kamas(craft_cost.missing_cost)
+        ))
+        .on_hover_text(
+            craft_cost
+                .unpriced_missing
+                .keys()
+                .map(|item| item.name.as_str())
+                .join("\n"),
+        );
+    }
+
     pub(crate) fn get_sized_texture(image: &Image) -> SizedTexture {
         SizedTexture {
             id: image.handle.id(),
@@ -1,36 +1,78 @@
 use std::{
+    borrow::Cow,
     cmp,
     collections::{BTreeMap, HashMap, HashSet},
-    sync::mpsc::{self, Receiver, Sender},
+    env,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
 };
 
+use chrono::Local;
 use diesel::{
     delete, insert_into,
     r2d2::{ConnectionManager, Pool},
-    update, PgConnection,
+    update, PgConnection, QueryResult,
 };
 use egui::{
-    CentralPanel, Color32, Context, Frame, InputState, PointerButton, Pos2, Rect, Rounding, Ui,
-    Vec2,
+    Align2, CentralPanel, Color32, ComboBox, Context, FontId, Frame, InputState, Key,
+    PointerButton, Pos2, Rect, Rounding, SidePanel, TextEdit, TopBottomPanel, Ui, Vec2,
 };
+use image::ImageError;
+use itertools::Itertools;
 use lombok::AllArgsConstructor;
 use tracing::{event, trace_span, warn, Level};
 
-use crate::database::{
-    models::{
-        drop::Drop,
-        item::{Item, ItemList},
-        map::Map,
-        monster::Monster,
-        monster_sub_area::MonsterSubArea,
-        sub_area::SubArea,
-        user_ingredient::UserIngredient,
-        user_item::UserItem,
+use crate::{
+    crafting::{
+        cost::{CraftCost, PriceList},
+        planner::CraftPlan,
+        project_progress::projects_progress,
+        recipe_graph::RecipeGraph,
+    },
+    database::{
+        models::{
+            drop::Drop,
+            farming_session::{FarmingSession, NewFarmingSession},
+            favorite::Favorite,
+            hint::Hint,
+            hint_category::HintCategory,
+            item::{Item, ItemList, Quantity},
+            journal_entry::{JournalEntry, NewJournalEntry},
+            map::Map,
+            monster::Monster,
+            monster_sub_area::MonsterSubArea,
+            profile::{NewProfile, Profile},
+            project::{NewProject, Project},
+            session_kill::SessionKill,
+            session_loot::SessionLoot,
+            sub_area::SubArea,
+            user_ingredient::UserIngredient,
+            user_item::UserItem,
+        },
+        schema::maps,
+    },
+    services::{
+        craft::{craft, Craft, CraftError},
+        database_worker::DatabaseWorker,
+        drop_rates::DropRates,
+        farming_route::{plan_route, DropSource},
+        heatmap::HeatmapMetric,
+        journal::{JournalReason, JournalTarget},
+        map_labels::{map_labels, MapLabel},
     },
-    schema::maps,
 };
 
-use super::{image::Image, items_window::ItemsWindow};
+use super::{
+    image::{Image, TileCache},
+    items_window::ItemsWindow,
+    tabs::items_tabs::{
+        farming_tab::{ActiveSession, FarmingAction},
+        history_tab::HistoryAction,
+        wish_list_tab::ProjectAction,
+    },
+};
 
 #[derive(Clone, Debug)]
 pub enum AsyncStatus<T> {
@@ -40,21 +82,21 @@ pub enum AsyncStatus<T> {
 
 #[derive(AllArgsConstructor)]
 pub struct MapMinMax {
-    x_min: i16,
-    x_max: i16,
-    y_min: i16,
-    y_max: i16,
+    pub(crate) x_min: i16,
+    pub(crate) x_max: i16,
+    pub(crate) y_min: i16,
+    pub(crate) y_max: i16,
 }
 
 pub type ItemsRelations = BTreeMap<
     Item, // item to craft
     (
-        i16, // quantity
+        Quantity, // quantity
         AsyncStatus<(
             HashMap<
                 Item, // one of the resources needed to make it
                 (
-                    i16,                                // quantity needed
+                    Quantity,                           // quantity needed
                     HashMap<Monster, HashSet<SubArea>>, // monsters and their sub areas
                 ),
             >,
@@ -63,27 +105,135 @@ pub type ItemsRelations = BTreeMap<
     ),
 >;
 
+// What the resources and monsters tabs and the map show, everything by default
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+pub struct ItemFilter {
+    // Only the wish list items of this project
+    pub project_id: Option<i32>,
+}
+
+impl ItemFilter {
+    pub fn is_empty(&self) -> bool {
+        self.project_id.is_none()
+    }
+
+    pub fn matches(&self, item: &Item, items_project: &HashMap<Item, i32>) -> bool {
+        match self.project_id {
+            Some(project_id) => items_project.get(item) == Some(&project_id),
+            None => true,
+        }
+    }
+
+    // Wish list items kept by the filter
+    pub fn apply<'a>(
+        &self,
+        items: &'a ItemsRelations,
+        items_project: &HashMap<Item, i32>,
+    ) -> Cow<'a, ItemsRelations> {
+        if self.is_empty() {
+            return Cow::Borrowed(items);
+        }
+
+        Cow::Owned(
+            items
+                .iter()
+                .filter(|(item, _)| self.matches(item, items_project))
+                .map(|(item, value)| (item.clone(), value.clone()))
+                .collect(),
+        )
+    }
+}
+
 type Ingredients = (
     Item,
-    i16,
-    HashMap<Item, (i16, HashMap<Monster, HashSet<SubArea>>)>,
+    Quantity,
+    HashMap<Item, (Quantity, HashMap<Monster, HashSet<SubArea>>)>,
     Vec<ItemList>,
 );
 
+// What the wish list still misses, computed once a frame for the map and the favorites
+pub(crate) struct MissingDrops {
+    // Quantity still missing of each ingredient
+    pub(crate) missing: HashMap<Item, Quantity>,
+    pub(crate) sources: Vec<DropSource>,
+    // Missing ingredients dropped in each sub-area
+    pub(crate) items_by_sub_area: HashMap<SubArea, HashSet<Item>>,
+}
+
+// Last journal entries, newest first
+type History = Vec<(JournalEntry, Item)>;
+
+// Wish list, inventory, projects, favorites and history
+type ProfileData = (
+    Vec<(Item, UserItem)>,
+    ItemList,
+    Vec<Project>,
+    Vec<Favorite>,
+    History,
+);
+
+// Count of the active session before a change, put back when the change isn't saved
+enum SessionCount {
+    Kills(Monster, i32),
+    Loot(Item, Quantity),
+}
+
+// Item changed, with the project it had in the wish list
+type JournalChange = (Item, JournalTarget, Quantity, Option<i32>);
+
+// Entries of the batch undone or redone, and the history after it
+type JournalBatch = (Vec<(JournalEntry, Item)>, History);
+
 pub struct MainWindow {
-    zoom_index: usize,
-    map_position: Pos2,
+    // Map pixels per pixel of the full map at zoom 1
+    pub(crate) scale: f32,
+    // Scale animated to, around zoom_anchor
+    pub(crate) target_scale: f32,
+    pub(crate) zoom_anchor: Pos2,
+    // Level of the tiles drawn, the smallest one not below the scale
+    pub(crate) zoom_index: usize,
+    pub(crate) map_position: Pos2,
     clicked_position: Option<Pos2>,
-    maps_images: HashMap<(u16, usize), AsyncStatus<Image>>,
-    images_number: (u8, u8),
-    map_min_max: MapMinMax,
-    sub_areas: HashMap<SubArea, Vec<Map>>,
-    current_sub_area: Option<SubArea>,
-    clicked_map: Option<(f32, f32)>,
-    map_tx: Sender<(Image, u16, usize)>,
-    map_rx: Receiver<(Image, u16, usize)>,
-    item_rx: Receiver<(Item, i16)>,
-    remove_item_rx: Receiver<(Item, i16, bool)>,
+    // (index, zoom index)
+    pub(crate) maps_images: TileCache<(u16, usize), Image>,
+    // Its tiles are drawn until the ones of the current zoom are loaded
+    pub(crate) previous_zoom_index: usize,
+    pub(crate) map_min_max: MapMinMax,
+    pub(crate) sub_areas: HashMap<SubArea, Vec<Map>>,
+    pub(crate) area_names: HashMap<i32, String>,
+    // Sub-areas the item tabs show, the selected ones or else the hovered or clicked one
+    current_sub_areas: HashSet<SubArea>,
+    // Built with ctrl+click and ctrl+drag
+    pub(crate) selected_sub_areas: HashSet<SubArea>,
+    // Where the ctrl+drag started, on screen
+    pub(crate) selection_start: Option<Pos2>,
+    pub(crate) clicked_map: Option<(f32, f32)>,
+    // In-game coordinates under the cursor
+    pub(crate) hovered_map: Option<(i16, i16)>,
+    coordinate_grid: bool,
+    map_labels: bool,
+    minimap: bool,
+    // Per map, drawn when their category is shown
+    pub(crate) hints: HashMap<(i16, i16), Vec<Hint>>,
+    // Sorted by name
+    pub(crate) hint_categories: Vec<HintCategory>,
+    pub(crate) hint_categories_shown: HashSet<i32>,
+    // Sorted by name
+    pub(crate) favorites: Vec<Favorite>,
+    favorites_panel: bool,
+    pub(crate) new_favorite_name: String,
+    // Biggest zones first
+    pub(crate) sub_area_labels: Vec<MapLabel>,
+    pub(crate) area_labels: Vec<MapLabel>,
+    pub(crate) go_to_text: String,
+    pub(crate) go_to_not_found: bool,
+    // Where the map was drawn last frame
+    pub(crate) map_viewport: Rect,
+    // Tiles decoded, or why they couldn't be
+    pub(crate) map_tx: Sender<(Result<Image, ImageError>, u16, usize)>,
+    pub(crate) map_rx: Receiver<(Result<Image, ImageError>, u16, usize)>,
+    item_rx: Receiver<(Item, Quantity)>,
+    remove_item_rx: Receiver<(Item, Quantity, bool)>,
     item_ingredients_tx: Sender<Ingredients>,
     item_ingredients_rx: Receiver<Ingredients>,
     item_image_tx: Sender<(Item, Image)>,
@@ -91,22 +241,85 @@ pub struct MainWindow {
     items_images: HashMap<Item, AsyncStatus<Image>>,
     monster_image_tx: Sender<(Monster, Image)>,
     monster_image_rx: Receiver<(Monster, Image)>,
-    new_ingredient_rx: Receiver<(Item, isize)>,
+    new_ingredient_rx: Receiver<(Item, Quantity)>,
     monsters_images: HashMap<Monster, AsyncStatus<Image>>,
     items: ItemsRelations,
     ingredients_quantity: ItemList,
     calculated_inventory: ItemList,
+    craft_plan: CraftPlan,
+    craft_cost: CraftCost,
+    prices: PriceList,
+    prices_rx: Receiver<PriceList>,
     items_window: ItemsWindow,
+    recipe_graph: Arc<RecipeGraph>,
+    pub(crate) profile: Profile,
+    profiles: Vec<Profile>,
+    new_profile_name: String,
+    // Sorted by priority
+    projects: Vec<Project>,
+    // Wish list items in a project
+    items_project: HashMap<Item, i32>,
+    projects_progress: HashMap<i32, f32>,
+    project_action_rx: Receiver<ProjectAction>,
+    item_filter: ItemFilter,
+    farming_route: bool,
+    // Uniform overlay when None
+    heatmap: Option<HeatmapMetric>,
+    active_session: Option<ActiveSession>,
+    drop_rates: DropRates,
+    // The session stopped, and the drop rates computed again with it
+    drop_rates_tx: Sender<(ActiveSession, QueryResult<DropRates>)>,
+    drop_rates_rx: Receiver<(ActiveSession, QueryResult<DropRates>)>,
+    farming_action_rx: Receiver<FarmingAction>,
+    // Result of the last craft, until dismissed
+    craft_message: Option<String>,
+    // Last write that failed, until dismissed
+    save_error: Option<String>,
+    history: History,
+    history_tx: Sender<(i32, QueryResult<History>)>,
+    history_rx: Receiver<(i32, QueryResult<History>)>,
+    history_action_rx: Receiver<HistoryAction>,
+    // (profile id, undo, batch), None when there is nothing to undo or redo
+    journal_batch_tx: Sender<(i32, bool, QueryResult<Option<JournalBatch>>)>,
+    journal_batch_rx: Receiver<(i32, bool, QueryResult<Option<JournalBatch>>)>,
+    // (profile id, data)
+    profile_data_tx: Sender<(i32, QueryResult<ProfileData>)>,
+    profile_data_rx: Receiver<(i32, QueryResult<ProfileData>)>,
+    // The profile created and every profile after it
+    new_profile_tx: Sender<(QueryResult<Profile>, QueryResult<Vec<Profile>>)>,
+    new_profile_rx: Receiver<(QueryResult<Profile>, QueryResult<Vec<Profile>>)>,
+    pub(crate) new_favorite_tx: Sender<QueryResult<Favorite>>,
+    pub(crate) new_favorite_rx: Receiver<QueryResult<Favorite>>,
+    new_project_tx: Sender<QueryResult<Project>>,
+    new_project_rx: Receiver<QueryResult<Project>>,
+    // (profile id, craft)
+    craft_tx: Sender<(i32, Result<Craft, CraftError>)>,
+    craft_rx: Receiver<(i32, Result<Craft, CraftError>)>,
+    // (profile id, what was saved, result)
+    saved_tx: Sender<(i32, &'static str, QueryResult<()>)>,
+    saved_rx: Receiver<(i32, &'static str, QueryResult<()>)>,
+    // (session id, count before the change, result)
+    session_count_tx: Sender<(i32, SessionCount, QueryResult<()>)>,
+    session_count_rx: Receiver<(i32, SessionCount, QueryResult<()>)>,
+    new_session_tx: Sender<(QueryResult<FarmingSession>, Option<SubArea>)>,
+    new_session_rx: Receiver<(QueryResult<FarmingSession>, Option<SubArea>)>,
+    pub(crate) database_worker: DatabaseWorker,
     pool: Pool<ConnectionManager<PgConnection>>,
 }
 
 impl MainWindow {
-    const IMAGE_SIZE: Vec2 = Vec2::new(250f32, 250f32);
-    const FULL_IMAGE_SIZE: Vec2 = Vec2::new(10000f32, 8000f32);
-    const ZOOMS: [f32; 5] = [0.2, 0.4, 0.6, 0.8, 1f32];
+    pub(crate) const IMAGE_SIZE: Vec2 = Vec2::new(250f32, 250f32);
+    pub(crate) const FULL_IMAGE_SIZE: Vec2 = Vec2::new(10000f32, 8000f32);
+    pub(crate) const ZOOMS: [f32; 5] = [0.2, 0.4, 0.6, 0.8, 1f32];
     const STARTING_ZOOM_INDEX: usize = 0;
-    const MAPS_RECT: Rect = Self::init_map_rect();
+    // Tiles of the biggest zoom are stretched above 1
+    pub(crate) const MAX_SCALE: f32 = 2f32;
+    // Scale multiplied by e for this many scrolled points
+    const SCROLL_PER_ZOOM: f32 = 600f32;
+    pub(crate) const MAPS_RECT: Rect = Self::init_map_rect();
     pub const ITEM_IMAGE_SIZE: Vec2 = Vec2 { x: 60f32, y: 60f32 };
+    const HISTORY_SIZE: i64 = 100;
+    const DEFAULT_MAP_CACHE_MB: usize = 256;
 
     const fn init_map_rect() -> Rect {
         let min = Pos2::new(360f32, 320f32);
@@ -116,7 +329,7 @@ impl MainWindow {
     }
 
     pub fn new(
-        _: &eframe::CreationContext<'_>,
+        creation_context: &eframe::CreationContext<'_>,
         pool: Pool<ConnectionManager<PgConnection>>,
     ) -> Self {
         let (map_tx, map_rx) = mpsc::channel();
@@ -126,21 +339,34 @@ impl MainWindow {
         let (item_image_tx, item_image_rx) = mpsc::channel();
         let (monster_image_tx, monster_image_rx) = mpsc::channel();
         let (new_ingredient_tx, new_ingredient_rx) = mpsc::channel();
+        let (history_tx, history_rx) = mpsc::channel();
+        let (history_action_tx, history_action_rx) = mpsc::channel();
+        let (project_action_tx, project_action_rx) = mpsc::channel();
+        let (farming_action_tx, farming_action_rx) = mpsc::channel();
+        let (drop_rates_tx, drop_rates_rx) = mpsc::channel();
+        let (journal_batch_tx, journal_batch_rx) = mpsc::channel();
+        let (profile_data_tx, profile_data_rx) = mpsc::channel();
+        let (new_profile_tx, new_profile_rx) = mpsc::channel();
+        let (new_favorite_tx, new_favorite_rx) = mpsc::channel();
+        let (new_project_tx, new_project_rx) = mpsc::channel();
+        let (craft_tx, craft_rx) = mpsc::channel();
+        let (saved_tx, saved_rx) = mpsc::channel();
+        let (session_count_tx, session_count_rx) = mpsc::channel();
+        let (new_session_tx, new_session_rx) = mpsc::channel();
 
         let mut connection = pool.get().unwrap();
 
         let zoom_index = Self::STARTING_ZOOM_INDEX;
-        let images_number = Self::image_number_from_zoom(zoom_index);
         let map_min_max = {
-            use diesel::dsl::{max, min};
+            use diesel::dsl;
             use diesel::prelude::*;
 
             let min_max = maps::table
                 .select((
-                    min(maps::x).assume_not_null(),
-                    max(maps::x).assume_not_null(),
-                    min(maps::y).assume_not_null(),
-                    max(maps::y).assume_not_null(),
+                    dsl::min(maps::x).assume_not_null(),
+                    dsl::max(maps::x).assume_not_null(),
+                    dsl::min(maps::y).assume_not_null(),
+                    dsl::max(maps::y).assume_not_null(),
                 ))
                 .first::<(i16, i16, i16, i16)>(&mut connection)
                 .unwrap();
@@ -179,54 +405,115 @@ impl MainWindow {
             maps_per_sub_area
         };
 
-        let ingredients_quantity = {
-            use crate::database::schema::{items, user_ingredients, user_items};
+        let area_names = {
+            use crate::database::schema::areas;
             use diesel::prelude::*;
 
-            let user_items: Vec<(UserItem, Item)> = user_items::table
-                .inner_join(items::table)
+            areas::table
+                .select((areas::id, areas::name))
+                .load::<(i32, String)>(&mut connection)
+                .unwrap()
+                .into_iter()
+                .collect()
+        };
+
+        let (sub_area_labels, area_labels) = map_labels(&sub_areas, &area_names);
+
+        let (hints, hint_categories) = {
+            use crate::database::schema::{hint_categories, hints};
+            use diesel::prelude::*;
+
+            let hints = hints::table
+                .select(Hint::as_select())
+                .order(hints::id)
                 .load(&mut connection)
-                .unwrap();
+                .unwrap()
+                .into_iter()
+                .into_group_map_by(|hint| (hint.x, hint.y));
 
-            user_items.into_iter().for_each(|(user_item, item)| {
-                item_tx.send((item, user_item.quantity as _)).unwrap()
-            });
+            let hint_categories = hint_categories::table
+                .select(HintCategory::as_select())
+                .order((hint_categories::name, hint_categories::id))
+                .load(&mut connection)
+                .unwrap();
 
-            ItemList::with_items(
-                user_ingredients::table
-                    .inner_join(items::table)
-                    .load::<(UserIngredient, Item)>(&mut connection)
-                    .unwrap()
-                    .into_iter()
-                    .map(|(user_ingredient, item)| (item, user_ingredient.quantity))
-                    .collect(),
-            )
+            (hints, hint_categories)
         };
 
-        let calculated_inventory =
-            Self::get_calculated_inventory(&ingredients_quantity, &mut connection);
+        let profiles = Self::load_profiles(&mut connection).unwrap();
+        let profile = profiles[0].clone();
+        let new_profile_name = String::new();
+
+        let recipe_graph = Arc::new(RecipeGraph::load(&mut connection));
+        let prices = PriceList::load(&mut connection);
+        let drop_rates = DropRates::load(&mut connection);
+        let database_worker = DatabaseWorker::new(pool.clone());
+        let (prices_tx, prices_rx) = mpsc::channel();
+
+        // Filled when the profile is loaded
+        let ingredients_quantity = ItemList::new();
+        let calculated_inventory = ItemList::new();
+        let history = Vec::new();
 
-        let current_sub_area = None;
+        let current_sub_areas = HashSet::new();
         let clicked_map = None;
 
-        let maps_images = HashMap::new();
+        // MAP_CACHE_MB sets the memory used by the map tiles
+        let maps_images = TileCache::new(
+            env::var("MAP_CACHE_MB")
+                .ok()
+                .and_then(|megabytes| megabytes.parse::<usize>().ok())
+                .unwrap_or(Self::DEFAULT_MAP_CACHE_MB)
+                * 1024
+                * 1024,
+        );
         let items = BTreeMap::new();
         let items_images = HashMap::new();
         let monsters_images = HashMap::new();
 
-        let items_window =
-            ItemsWindow::new(pool.clone(), item_tx, new_ingredient_tx, remove_item_tx);
+        let items_window = ItemsWindow::new(
+            pool.clone(),
+            item_tx,
+            new_ingredient_tx,
+            remove_item_tx,
+            history_action_tx,
+            database_worker.clone(),
+            prices_tx,
+            project_action_tx,
+            farming_action_tx,
+        );
 
-        Self {
+        let mut main_window = Self {
+            scale: Self::ZOOMS[zoom_index],
+            target_scale: Self::ZOOMS[zoom_index],
+            zoom_anchor: Pos2::ZERO,
             zoom_index,
             map_position: Pos2::ZERO,
             clicked_position: None,
             maps_images,
-            images_number,
+            previous_zoom_index: zoom_index,
             map_min_max,
             sub_areas,
-            current_sub_area,
+            area_names,
+            current_sub_areas,
+            selected_sub_areas: HashSet::new(),
+            selection_start: None,
             clicked_map,
+            hovered_map: None,
+            coordinate_grid: false,
+            map_labels: true,
+            minimap: true,
+            hints,
+            hint_categories,
+            hint_categories_shown: HashSet::new(),
+            favorites: Vec::new(),
+            favorites_panel: false,
+            new_favorite_name: String::new(),
+            sub_area_labels,
+            area_labels,
+            go_to_text: String::new(),
+            go_to_not_found: false,
+            map_viewport: Rect::ZERO,
             map_tx,
             map_rx,
             item_rx,
@@ -243,49 +530,397 @@ impl MainWindow {
             items,
             ingredients_quantity,
             calculated_inventory,
+            craft_plan: CraftPlan::default(),
+            craft_cost: CraftCost::default(),
+            prices,
+            prices_rx,
             items_window,
+            recipe_graph,
+            profile: profile.clone(),
+            profiles,
+            new_profile_name,
+            projects: Vec::new(),
+            items_project: HashMap::new(),
+            projects_progress: HashMap::new(),
+            project_action_rx,
+            item_filter: ItemFilter::default(),
+            farming_route: false,
+            heatmap: None,
+            active_session: None,
+            drop_rates,
+            drop_rates_tx,
+            drop_rates_rx,
+            farming_action_rx,
+            craft_message: None,
+            save_error: None,
+            history,
+            history_tx,
+            history_rx,
+            history_action_rx,
+            journal_batch_tx,
+            journal_batch_rx,
+            profile_data_tx,
+            profile_data_rx,
+            new_profile_tx,
+            new_profile_rx,
+            new_favorite_tx,
+            new_favorite_rx,
+            new_project_tx,
+            new_project_rx,
+            craft_tx,
+            craft_rx,
+            saved_tx,
+            saved_rx,
+            session_count_tx,
+            session_count_rx,
+            new_session_tx,
+            new_session_rx,
+            database_worker,
             pool,
+        };
+
+        main_window.switch_profile(&creation_context.egui_ctx, profile);
+
+        main_window
+    }
+
+    fn load_profiles(connection: &mut PgConnection) -> QueryResult<Vec<Profile>> {
+        use crate::database::schema::profiles;
+        use diesel::prelude::*;
+
+        let profiles = profiles::table
+            .select(Profile::as_select())
+            .order(profiles::id)
+            .load(connection)?;
+
+        if profiles.is_empty() {
+            let profile = insert_into(profiles::table)
+                .values(NewProfile::new("Default".to_owned()))
+                .returning(Profile::as_returning())
+                .get_result(connection)?;
+
+            return Ok(vec![profile]);
         }
+
+        Ok(profiles)
     }
 
-    fn draw_map_body_loop(&mut self, x: i32, y: i32, pos: Pos2, ui: &Ui) {
-        let new_x = x - pos.x as i32;
-        let new_y = y - pos.y as i32;
-
-        let x_index = (new_x as f32 / Self::IMAGE_SIZE.x).floor() as i8;
-        let y_index = (new_y as f32 / Self::IMAGE_SIZE.y).floor() as i8;
-
-        if (0..self.images_number.0 as i8).contains(&x_index)
-            && (0..self.images_number.1 as i8).contains(&y_index)
-        {
-            let index = y_index as u16 * self.images_number.0 as u16 + x_index as u16;
-            if let Some(image_status) = self.maps_images.get_mut(&(index, self.zoom_index)) {
-                if let AsyncStatus::Ready(image) = image_status {
-                    let pos = Pos2::new(x as f32, y as f32);
-
-                    ui.painter().image(
-                        image.handle.id(),
-                        Rect::from_two_pos(pos, pos + image.handle.size_vec2()),
-                        Rect::from_min_max(Pos2::ZERO, Pos2::new(1f32, 1f32)),
-                        Color32::WHITE,
-                    );
+    fn load_profile(profile_id: i32, connection: &mut PgConnection) -> QueryResult<ProfileData> {
+        use crate::database::schema::{favorites, items, projects, user_ingredients, user_items};
+        use diesel::prelude::*;
+
+        let user_items = user_items::table
+            .inner_join(items::table)
+            .filter(user_items::profile_id.eq(profile_id))
+            .load::<(UserItem, Item)>(connection)?
+            .into_iter()
+            .map(|(user_item, item)| (item, user_item))
+            .collect();
+
+        let user_ingredients = ItemList::with_items(
+            user_ingredients::table
+                .inner_join(items::table)
+                .filter(user_ingredients::profile_id.eq(profile_id))
+                .load::<(UserIngredient, Item)>(connection)?
+                .into_iter()
+                .map(|(user_ingredient, item)| (item, user_ingredient.quantity))
+                .collect(),
+        );
+
+        let projects = projects::table
+            .filter(projects::profile_id.eq(profile_id))
+            .select(Project::as_select())
+            .order((projects::priority, projects::id))
+            .load(connection)?;
 
-                    image.used = true;
+        let favorites = favorites::table
+            .filter(favorites::profile_id.eq(profile_id))
+            .select(Favorite::as_select())
+            .order(favorites::name)
+            .load(connection)?;
+
+        let history = Self::load_history(profile_id, connection)?;
+
+        Ok((user_items, user_ingredients, projects, favorites, history))
+    }
+
+    fn switch_profile(&mut self, ctx: &Context, profile: Profile) {
+        let span = trace_span!("switch_profile");
+        let _guard = span.enter();
+
+        // Changes made to the previous profile that were not handled yet
+        self.check_for_new_items(ctx);
+        self.check_for_removed_item(ctx);
+        self.check_for_new_ingredient_in_inventory(ctx);
+        self.stop_farming_session(ctx);
+
+        self.profile = profile;
+        self.reload_profile(ctx);
+    }
+
+    // Shows the profile as saved in the database
+    fn reload_profile(&mut self, ctx: &Context) {
+        self.items.clear();
+        self.items_project.clear();
+        self.projects.clear();
+        self.favorites.clear();
+        self.ingredients_quantity = ItemList::new();
+        self.history.clear();
+        self.update_calculated_inventory();
+        self.current_sub_areas.clear();
+        self.clicked_map = None;
+        self.item_filter = ItemFilter::default();
+
+        // Queued after the writes of the previous profile
+        let profile_id = self.profile.id;
+        self.database_worker
+            .query(ctx, self.profile_data_tx.clone(), move |connection| {
+                (profile_id, Self::load_profile(profile_id, connection))
+            });
+    }
+
+    // Writes of the profile that can fail, they're checked in check_for_saves
+    pub(crate) fn save(
+        &self,
+        ctx: &Context,
+        what: &'static str,
+        job: impl FnOnce(&mut PgConnection) -> QueryResult<()> + Send + 'static,
+    ) {
+        let profile_id = self.profile.id;
+
+        self.database_worker
+            .query(ctx, self.saved_tx.clone(), move |connection| {
+                (profile_id, what, job(connection))
+            });
+    }
+
+    // A write failed: the error is shown and the profile loaded again, so nothing unsaved is shown
+    fn check_for_saves(&mut self, ctx: &Context) {
+        let current_profile_id = self.profile.id;
+        let errors = self
+            .saved_rx
+            .try_iter()
+            .filter(|(profile_id, _, _)| *profile_id == current_profile_id)
+            .filter_map(|(_, what, result)| result.err().map(|error| (what, error)))
+            .collect_vec();
+
+        errors
+            .iter()
+            .for_each(|(what, error)| warn!("{what} not saved: {error}"));
+
+        if let Some((what, error)) = errors.last() {
+            self.save_error = Some(format!("{what} not saved: {error}"));
+            self.reload_profile(ctx);
+        }
+    }
+
+    // Data of the profile switched to, and profiles created
+    fn check_for_profiles(&mut self, ctx: &Context) {
+        let current_profile_id = self.profile.id;
+        let profile_data = self.profile_data_rx.try_iter().collect_vec();
+        profile_data
+            .into_iter()
+            .filter(|(profile_id, _)| *profile_id == current_profile_id)
+            .for_each(|(_, profile_data)| {
+                let (user_items, user_ingredients, projects, favorites, history) =
+                    match profile_data {
+                        Ok(profile_data) => profile_data,
+                        Err(error) => {
+                            warn!("Profile not loaded: {error}");
+                            return;
+                        }
+                    };
+
+                user_items.iter().for_each(|(item, user_item)| {
+                    self.set_wish_list_quantity(ctx, item, user_item.quantity);
+                });
+
+                self.items_project = user_items
+                    .into_iter()
+                    .filter_map(|(item, user_item)| user_item.project_id.map(|id| (item, id)))
+                    .collect();
+                self.projects = projects;
+                self.favorites = favorites;
+                self.ingredients_quantity = user_ingredients;
+                self.update_calculated_inventory();
+                self.history = history;
+            });
+
+        let new_profiles = self.new_profile_rx.try_iter().collect_vec();
+        new_profiles.into_iter().for_each(|(profile, profiles)| {
+            // Another instance may have created the same name meanwhile
+            match profiles {
+                Ok(profiles) => self.profiles = profiles,
+                Err(error) => warn!("Profiles not loaded: {error}"),
+            }
+
+            match profile {
+                Ok(profile) => {
+                    self.new_profile_name.clear();
+                    self.switch_profile(ctx, profile);
                 }
-            } else {
-                self.maps_images
-                    .insert((index, self.zoom_index), AsyncStatus::Loading);
-                self.load_map_image(ui.ctx().clone(), index);
+                Err(error) => warn!("Profile not created: {error}"),
             }
-        }
+        });
     }
 
-    fn central_panel_ui(&mut self, ui: &Ui) {
+    fn profiles_panel_ui(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Profile");
+
+            let mut selected_profile = None;
+            ComboBox::from_id_salt("profile")
+                .selected_text(&self.profile.name)
+                .show_ui(ui, |ui| {
+                    self.profiles.iter().for_each(|profile| {
+                        if ui
+                            .selectable_label(*profile == self.profile, &profile.name)
+                            .clicked()
+                        {
+                            selected_profile = Some(profile.clone());
+                        }
+                    });
+                });
+
+            if let Some(profile) = selected_profile.filter(|profile| *profile != self.profile) {
+                self.switch_profile(ui.ctx(), profile);
+            }
+
+            ui.separator();
+
+            ui.label("Project");
+
+            let item_filter = self.item_filter.clone();
+            ComboBox::from_id_salt("project filter")
+                .selected_text(
+                    self.projects
+                        .iter()
+                        .find(|project| Some(project.id) == self.item_filter.project_id)
+                        .map(|project| project.name.as_str())
+                        .unwrap_or("All"),
+                )
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.item_filter.project_id, None, "All");
+                    self.projects.iter().for_each(|project| {
+                        ui.selectable_value(
+                            &mut self.item_filter.project_id,
+                            Some(project.id),
+                            &project.name,
+                        );
+                    });
+                });
+
+            if self.item_filter != item_filter {
+                self.update_calculated_inventory();
+            }
+
+            ui.checkbox(&mut self.farming_route, "Farming route");
+
+            ComboBox::from_id_salt("heatmap")
+                .selected_text(
+                    self.heatmap
+                        .map(|metric| format!("Heatmap: {metric}"))
+                        .unwrap_or("No heatmap".to_owned()),
+                )
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.heatmap, None, "No heatmap");
+                    HeatmapMetric::ALL.into_iter().for_each(|metric| {
+                        ui.selectable_value(&mut self.heatmap, Some(metric), metric.to_string());
+                    });
+                });
+
+            ui.checkbox(&mut self.coordinate_grid, "Coordinate grid");
+            ui.checkbox(&mut self.map_labels, "Labels");
+            ui.checkbox(&mut self.minimap, "Minimap");
+
+            self.hints_menu_ui(ui);
+
+            ui.checkbox(&mut self.favorites_panel, "Favorites");
+
+            ui.separator();
+
+            self.selection_label_ui(ui);
+
+            ui.separator();
+
+            let go_to_response = ui.add(
+                TextEdit::singleline(&mut self.go_to_text)
+                    .hint_text("Go to [x, y] or zone")
+                    .desired_width(160f32),
+            );
+            if go_to_response.changed() {
+                self.go_to_not_found = false;
+            }
+            if go_to_response.lost_focus() && ui.input(|input| input.key_pressed(Key::Enter)) {
+                self.go_to();
+            }
+            if self.go_to_not_found {
+                ui.colored_label(Color32::RED, "Not found");
+            }
+
+            ui.separator();
+
+            ui.text_edit_singleline(&mut self.new_profile_name);
+            let new_profile_name = self.new_profile_name.trim().to_owned();
+            let name_available = !new_profile_name.is_empty()
+                && !self
+                    .profiles
+                    .iter()
+                    .any(|profile| profile.name == new_profile_name);
+
+            if ui
+                .add_enabled(name_available, egui::Button::new("New profile"))
+                .clicked()
+            {
+                use crate::database::schema::profiles;
+                use diesel::prelude::*;
+
+                self.database_worker.query(
+                    ui.ctx(),
+                    self.new_profile_tx.clone(),
+                    move |connection| {
+                        let profile = insert_into(profiles::table)
+                            .values(NewProfile::new(new_profile_name))
+                            .returning(Profile::as_returning())
+                            .get_result(connection);
+                        (profile, Self::load_profiles(connection))
+                    },
+                );
+            }
+
+            if let Some(craft_message) = &self.craft_message {
+                ui.separator();
+                ui.label(craft_message);
+
+                if ui.small_button("x").clicked() {
+                    self.craft_message = None;
+                }
+            }
+
+            if let Some(save_error) = &self.save_error {
+                ui.separator();
+                ui.colored_label(Color32::RED, save_error);
+
+                if ui.small_button("x").clicked() {
+                    self.save_error = None;
+                }
+            }
+        });
+    }
+
+    fn central_panel_ui(&mut self, ui: &Ui, missing_drops: &MissingDrops) {
         let ctx = ui.ctx();
-        let ui_contains_pointer = ui.ui_contains_pointer();
-        let (pointer_pos, double_clicked, right_clicked) =
+        // The minimap handles its own clicks
+        let over_minimap = self.minimap
+            && ctx
+                .pointer_hover_pos()
+                .is_some_and(|pos| self.minimap_rect().contains(pos));
+        let ui_contains_pointer = ui.ui_contains_pointer() && !over_minimap;
+        let (pointer_pos, double_clicked, right_clicked, selection) =
             ui.input(|input_state| self.on_input(input_state, ui_contains_pointer));
 
+        self.animate_zoom(ctx);
+
         if right_clicked {
             self.clicked_map = None;
         }
@@ -298,69 +933,119 @@ impl MainWindow {
 
         let pointer_pos_on_map = pointer_pos.map(|pos| (pos - fullmap_position).to_pos2());
         let pointer_pos_on_map_zoomed =
-            pointer_pos_on_map.map(|pos| (pos.to_vec2() / Self::ZOOMS[self.zoom_index]).to_pos2());
+            pointer_pos_on_map.map(|pos| (pos.to_vec2() / self.scale).to_pos2());
+
+        self.map_viewport = ui.max_rect();
 
         // Draw full map images
-        self.reset_images_flags();
-
-        let size = ui.available_size();
-        let left = fullmap_position.x % Self::IMAGE_SIZE.x;
-        let top = fullmap_position.y % Self::IMAGE_SIZE.y;
-        let right = size.x;
-        let bottom = size.y;
-        for x in (left as i32..=right as i32).step_by(Self::IMAGE_SIZE.x as usize) {
-            for y in (top as i32..=bottom as i32).step_by(Self::IMAGE_SIZE.y as usize) {
-                self.draw_map_body_loop(x, y, fullmap_position, ui);
-            }
+        self.maps_images.next_frame();
+
+        // Part of the full map at zoom 1 on screen
+        let view = Rect::from_min_max(
+            ((self.map_viewport.min - fullmap_position) / self.scale).to_pos2(),
+            ((self.map_viewport.max - fullmap_position) / self.scale).to_pos2(),
+        );
+        let visible_loading = self.draw_map_tiles(ui, fullmap_position, view);
+        self.prefetch_map_tiles(ctx, view, visible_loading);
+
+        if self.coordinate_grid {
+            self.coordinate_grid_ui(ui, fullmap_position);
         }
 
-        self.check_images_flags();
+        self.current_sub_areas.clear();
+
+        let hovered_index = pointer_pos_on_map
+            .zip(pointer_pos_on_map_zoomed)
+            .filter(|(_, pos_zoomed)| Self::MAPS_RECT.contains(*pos_zoomed))
+            .map(|(pos, _)| self.map_index_at(pos));
 
-        self.current_sub_area = None;
+        self.hovered_map = hovered_index.map(|(x_index, y_index)| {
+            (
+                x_index as i16 + self.map_min_max.x_min,
+                y_index as i16 + self.map_min_max.y_min,
+            )
+        });
+
+        // The last one wins: a selection replaces the double-clicked zone and a double click the
+        // selected ones
+        if let Some(selection) = selection {
+            self.clicked_map = None;
+            self.select_sub_areas(selection, fullmap_position);
+        }
 
         if let Some((x_index, y_index)) = self.clicked_map {
             self.draw_map_and_filter_with_sub_area(ui, fullmap_position, x_index, y_index);
-        } else if let Some(pointer_pos_on_map_zoomed) = pointer_pos_on_map_zoomed {
-            if Self::MAPS_RECT.contains(pointer_pos_on_map_zoomed) {
-                let zoom = Self::ZOOMS[self.zoom_index];
-                let rect_size = Vec2::new(
-                    (Self::MAPS_RECT.width() * zoom)
-                        / (self.map_min_max.x_max - self.map_min_max.x_min + 1) as f32,
-                    (Self::MAPS_RECT.height() * zoom)
-                        / (self.map_min_max.y_max - self.map_min_max.y_min + 1) as f32,
-                );
+        } else if let Some((x_index, y_index)) = hovered_index {
+            if double_clicked {
+                self.clicked_map = Some((x_index, y_index));
+                self.selected_sub_areas.clear();
+            }
 
-                let offset = (
-                    (Self::MAPS_RECT.left() * zoom) % rect_size.x,
-                    (Self::MAPS_RECT.top() * zoom) % rect_size.y,
-                );
+            self.draw_map_and_filter_with_sub_area(ui, fullmap_position, x_index, y_index);
+        }
 
-                let x_index =
-                    ((pointer_pos_on_map_zoomed.x * zoom - offset.0) / rect_size.x).floor() - 5f32;
-                let y_index =
-                    ((pointer_pos_on_map_zoomed.y * zoom - offset.1) / rect_size.y).floor() - 6f32;
+        if !self.selected_sub_areas.is_empty() {
+            self.current_sub_areas = self.selected_sub_areas.clone();
+        }
 
-                if double_clicked {
-                    self.clicked_map = Some((x_index, y_index));
-                }
+        self.overlays_ui(ui, fullmap_position, missing_drops);
+        self.selection_ui(ui, fullmap_position);
 
-                self.draw_map_and_filter_with_sub_area(ui, fullmap_position, x_index, y_index);
-            }
+        if !self.hint_categories_shown.is_empty() {
+            self.hints_ui(ui, fullmap_position, pointer_pos);
         }
 
-        let mut still_needed_ingredients_total = HashMap::new();
+        if self.map_labels {
+            self.map_labels_ui(ui, fullmap_position);
+        }
+
+        if self.minimap {
+            self.minimap_ui(ui, fullmap_position, missing_drops);
+        }
+    }
+
+    // Sub-areas of the missing ingredients, as a farming route, a heatmap or a uniform overlay
+    fn overlays_ui(&self, ui: &Ui, fullmap_position: Pos2, missing_drops: &MissingDrops) {
+        if self.farming_route {
+            self.farming_route_ui(ui, fullmap_position, &missing_drops.sources);
+            return;
+        }
+
+        if let Some(metric) = self.heatmap {
+            self.heatmap_ui(ui, fullmap_position, missing_drops, metric);
+            return;
+        }
 
-        self.items.iter().for_each(|(_, (quantity, ingredients))| {
+        self.sub_areas
+            .iter()
+            .filter(|(sub_area, _)| missing_drops.items_by_sub_area.contains_key(sub_area))
+            .for_each(|(_, maps)| {
+                maps.iter().for_each(|map| {
+                    self.map_rect_on_pos(ui, map.x as _, map.y as _, fullmap_position, None);
+                });
+            });
+    }
+
+    // Ingredients of the items with their needed and owned quantities, when some are missing
+    fn still_needed_ingredients<'a>(
+        &self,
+        items: &'a ItemsRelations,
+    ) -> HashMap<&'a Item, (Quantity, Quantity)> {
+        let mut still_needed_ingredients_total: HashMap<&Item, (Quantity, Quantity)> =
+            HashMap::new();
+
+        items.iter().for_each(|(_, (quantity, ingredients))| {
             if let AsyncStatus::Ready((ingredients, _steps)) = ingredients {
                 ingredients.iter().for_each(|(ingredient, (needed, _))| {
                     still_needed_ingredients_total
                         .entry(ingredient)
                         .and_modify(|(needed_total, _)| {
-                            *needed_total += needed * quantity;
+                            *needed_total =
+                                needed_total.saturating_add(needed.saturating_mul(*quantity));
                         })
                         .or_insert_with(|| {
                             (
-                                *needed * quantity,
+                                needed.saturating_mul(*quantity),
                                 *self.ingredients_quantity.get(ingredient).unwrap_or(&0),
                             )
                         });
@@ -370,26 +1055,121 @@ impl MainWindow {
 
         still_needed_ingredients_total.retain(|_, (needed, in_inventory)| in_inventory < needed);
 
-        let mut sub_areas_to_draw = HashSet::new();
-        self.items.iter().for_each(|(_, (_, ingredients))| {
+        still_needed_ingredients_total
+    }
+
+    // Every sub-area where a missing ingredient drops
+    fn missing_drop_sources(
+        &self,
+        items: &ItemsRelations,
+        still_needed_ingredients: &HashMap<&Item, (Quantity, Quantity)>,
+    ) -> Vec<DropSource> {
+        let mut drop_sources = Vec::new();
+        items.iter().for_each(|(_, (_, ingredients))| {
             if let AsyncStatus::Ready((ingredients, _steps)) = ingredients {
                 ingredients.iter().for_each(|(ingredient, (_, monsters))| {
-                    if still_needed_ingredients_total.contains_key(ingredient) {
-                        monsters.iter().for_each(|(_, sub_areas)| {
-                            sub_areas_to_draw.extend(sub_areas);
+                    if still_needed_ingredients.contains_key(ingredient) {
+                        monsters.iter().for_each(|(monster, sub_areas)| {
+                            let drop_rate = self.drop_rates.rate(monster, ingredient);
+
+                            drop_sources.extend(sub_areas.iter().map(|sub_area| DropSource {
+                                sub_area: sub_area.clone(),
+                                item: ingredient.clone(),
+                                drop_rate,
+                                monster_level: None,
+                            }));
                         });
                     }
                 });
             }
         });
 
-        self.sub_areas
+        drop_sources
+    }
+
+    fn missing_drops(&self) -> MissingDrops {
+        let items = self.item_filter.apply(&self.items, &self.items_project);
+        let still_needed_ingredients = self.still_needed_ingredients(&items);
+        let sources = self.missing_drop_sources(&items, &still_needed_ingredients);
+
+        let mut items_by_sub_area: HashMap<SubArea, HashSet<Item>> = HashMap::new();
+        sources.iter().for_each(|source| {
+            items_by_sub_area
+                .entry(source.sub_area.clone())
+                .or_default()
+                .insert(source.item.clone());
+        });
+
+        MissingDrops {
+            missing: still_needed_ingredients
+                .into_iter()
+                .map(|(item, (needed, in_inventory))| (item.clone(), needed - in_inventory))
+                .collect(),
+            sources,
+            items_by_sub_area,
+        }
+    }
+
+    // Numbered sub-areas covering every missing drop instead of all the sub-areas
+    fn farming_route_ui(&self, ui: &Ui, fullmap_position: Pos2, drop_sources: &[DropSource]) {
+        let positions = self
+            .sub_areas
             .iter()
-            .filter(|(sub_area, _)| sub_areas_to_draw.contains(sub_area))
-            .for_each(|(_, maps)| {
-                maps.iter().for_each(|map| {
-                    self.map_rect_on_pos(ui, map.x as _, map.y as _, fullmap_position, None);
-                });
+            .filter(|(_, maps)| !maps.is_empty())
+            .map(|(sub_area, maps)| {
+                let count = maps.len() as f32;
+                let x = maps.iter().map(|map| map.x as f32).sum::<f32>() / count;
+                let y = maps.iter().map(|map| map.y as f32).sum::<f32>() / count;
+                (sub_area.id, (x, y))
+            })
+            .collect::<HashMap<_, _>>();
+
+        plan_route(drop_sources, &positions)
+            .iter()
+            .enumerate()
+            .for_each(|(index, step)| {
+                let Some(maps) = self.sub_areas.get(&step.sub_area) else {
+                    return;
+                };
+
+                let rect = maps
+                    .iter()
+                    .map(|map| {
+                        self.map_rect_on_pos(
+                            ui,
+                            map.x as _,
+                            map.y as _,
+                            fullmap_position,
+                            Some(Color32::from_rgba_unmultiplied(255, 140, 0, 70)),
+                        )
+                    })
+                    .reduce(|rect, map_rect| rect.union(map_rect));
+
+                if let Some(rect) = rect {
+                    ui.painter().text(
+                        rect.center(),
+                        Align2::CENTER_BOTTOM,
+                        (index + 1).to_string(),
+                        FontId::proportional(18f32),
+                        Color32::WHITE,
+                    );
+                    ui.painter().text(
+                        rect.center(),
+                        Align2::CENTER_TOP,
+                        step.items
+                            .iter()
+                            .map(|item| {
+                                if step.unobserved.contains(item) {
+                                    format!("{} (rate not observed)", item.name)
+                                } else {
+                                    item.name.clone()
+                                }
+                            })
+                            .join("\n"),
+                        FontId::proportional(11f32),
+                        Color32::WHITE,
+                    );
+                }
             });
     }
 
@@ -430,7 +1210,7 @@ impl MainWindow {
                 }
                 false
             }) {
-                self.current_sub_area = Some(sub_area.0.clone());
+                self.current_sub_areas = HashSet::from([sub_area.0.clone()]);
             }
         }
     }
@@ -442,27 +1222,10 @@ impl MainWindow {
         y_index: f32,
         fullmap_position: Pos2,
         color: Option<Color32>,
-    ) {
-        let x_index = x_index + 5f32;
-        let y_index = y_index + 6f32;
-
-        let zoom = Self::ZOOMS[self.zoom_index];
-        let rect_size = Vec2::new(
-            (Self::MAPS_RECT.width() * zoom)
-                / (self.map_min_max.x_max - self.map_min_max.x_min + 1) as f32,
-            (Self::MAPS_RECT.height() * zoom)
-                / (self.map_min_max.y_max - self.map_min_max.y_min + 1) as f32,
-        );
-
-        let offset = (
-            (Self::MAPS_RECT.left() * zoom) % rect_size.x,
-            (Self::MAPS_RECT.top() * zoom) % rect_size.y,
-        );
-
-        let x = x_index * rect_size.x + fullmap_position.x + offset.0;
-        let y = y_index * rect_size.y + fullmap_position.y + offset.1;
-
-        let map_pos = Pos2::new(x, y);
+    ) -> Rect {
+        let rect_size = self.map_cell_size();
+        let map_pos =
+            self.map_cell_origin(fullmap_position) + Vec2::new(x_index, y_index) * rect_size;
 
         let rect = Rect::from_two_pos(map_pos, map_pos + rect_size);
         ui.painter().rect_filled(
@@ -470,16 +1233,18 @@ impl MainWindow {
             Rounding::ZERO,
             color.unwrap_or(Color32::from_rgba_unmultiplied(60, 180, 255, 50)),
         );
+
+        rect
     }
 
-    fn map_rect_on_pos(
+    pub(crate) fn map_rect_on_pos(
         &self,
         ui: &Ui,
         x_index: f32,
         y_index: f32,
         fullmap_position: Pos2,
         color: Option<Color32>,
-    ) {
+    ) -> Rect {
         self.map_rect_on_index(
             ui,
             x_index - self.map_min_max.x_min as f32,
@@ -493,10 +1258,16 @@ impl MainWindow {
         &mut self,
         input_state: &InputState,
         ui_contains_pointer: bool,
-    ) -> (Option<Pos2>, bool, bool) {
+    ) -> (Option<Pos2>, bool, bool, Option<Rect>) {
+        let selecting = input_state.modifiers.command;
+
         if ui_contains_pointer {
             if input_state.pointer.primary_pressed() {
-                self.clicked_position = input_state.pointer.interact_pos();
+                if selecting {
+                    self.selection_start = input_state.pointer.interact_pos();
+                } else {
+                    self.clicked_position = input_state.pointer.interact_pos();
+                }
             }
 
             if input_state
@@ -506,15 +1277,26 @@ impl MainWindow {
                 self.map_position = Pos2::ZERO;
             }
 
-            let scroll_delta = input_state.smooth_scroll_delta.y;
-            if scroll_delta > 0f32 {
-                self.zoom_out(input_state.pointer.interact_pos().unwrap());
-            } else if scroll_delta < 0f32 {
-                self.zoom_in(input_state.pointer.interact_pos().unwrap());
+            let scroll_delta = input_state.raw_scroll_delta.y;
+            if scroll_delta != 0f32 {
+                if let Some(pointer_pos) = input_state.pointer.interact_pos() {
+                    self.target_scale = (self.target_scale
+                        * (scroll_delta / Self::SCROLL_PER_ZOOM).exp())
+                    .clamp(Self::ZOOMS[0], Self::MAX_SCALE);
+                    self.zoom_anchor = pointer_pos;
+                }
             }
         }
 
+        let mut selection = None;
         if input_state.pointer.primary_released() {
+            if let Some(selection_start) = self.selection_start.take() {
+                selection = input_state
+                    .pointer
+                    .interact_pos()
+                    .map(|pos| Rect::from_two_pos(selection_start, pos));
+            }
+
             if let Some(clicked_position) = self.clicked_position {
                 self.map_position += input_state.pointer.interact_pos().unwrap() - clicked_position;
                 self.clicked_position = None;
@@ -528,46 +1310,10 @@ impl MainWindow {
 
         let double_clicked = input_state
             .pointer
-            .button_double_clicked(PointerButton::Primary);
+            .button_double_clicked(PointerButton::Primary)
+            && !selecting;
         let right_clicked = input_state.pointer.secondary_clicked() && ui_contains_pointer;
-        (pointer_pos, double_clicked, right_clicked)
-    }
-
-    fn reset_images_flags(&mut self) {
-        let span = trace_span!("reset_images_flags");
-        let _guard = span.enter();
-
-        self.maps_images.iter_mut().for_each(|(_, image_status)| {
-            if let AsyncStatus::Ready(ref mut image) = image_status {
-                image.used = false;
-            }
-        });
-    }
-
-    fn check_images_flags(&mut self) {
-        let span = trace_span!("check_images_flags");
-        let _guard = span.enter();
-
-        self.maps_images.retain(|_, image_status| {
-            if let AsyncStatus::Ready(ref image) = image_status {
-                return image.used;
-            }
-            true
-        });
-    }
-
-    fn load_map_image(&mut self, ctx: Context, index: u16) {
-        let span = trace_span!("load_map_image");
-        let _guard = span.enter();
-
-        let tx = self.map_tx.clone();
-        let zoom_index = self.zoom_index;
-        let zoom = Self::ZOOMS[zoom_index];
-        tokio::spawn(async move {
-            let image = Image::map_from_ui_and_index(&ctx, index, zoom);
-            tx.send((image, index, zoom_index)).unwrap();
-            ctx.request_repaint();
-        });
+        (pointer_pos, double_clicked, right_clicked, selection)
     }
 
     fn load_item_image(tx: Sender<(Item, Image)>, ctx: Context, item: Item) {
@@ -575,7 +1321,13 @@ impl MainWindow {
         let _guard = span.enter();
 
         tokio::spawn(async move {
-            let image = Image::item_from_image_id(&ctx, item.image_id);
+            let image = match Image::item_from_image_id(&ctx, item.image_id) {
+                Ok(image) => image,
+                Err(error) => {
+                    warn!("Image of {} not loaded: {error}", item.name);
+                    return;
+                }
+            };
             event!(Level::INFO, "Loaded item image {}", item.name);
             tx.send((item, image)).unwrap();
             ctx.request_repaint();
@@ -587,170 +1339,439 @@ impl MainWindow {
         let _guard = span.enter();
 
         tokio::spawn(async move {
-            let image = Image::monster_from_id(&ctx, monster.id);
+            let image = match Image::monster_from_id(&ctx, monster.id) {
+                Ok(image) => image,
+                Err(error) => {
+                    warn!("Image of {} not loaded: {error}", monster.name);
+                    return;
+                }
+            };
             tx.send((monster, image)).unwrap();
             ctx.request_repaint();
         });
     }
 
-    fn check_for_new_map_images(&mut self) {
-        let span = trace_span!("check_for_new_images");
-        let _guard = span.enter();
+    // New item to craft / to have
+    fn check_for_new_items(&mut self, ctx: &Context) {
+        let new_items = self.item_rx.try_iter().collect_vec();
+
+        new_items.into_iter().for_each(|(item, quantity)| {
+            let delta = self.change_wish_list(ctx, &item, quantity);
+            let project_id = self.items_project.get(&item).copied();
+            self.write_journal(
+                ctx,
+                vec![(item, JournalTarget::WishList, delta, project_id)],
+                JournalReason::Manual,
+            );
+        });
+    }
 
-        self.map_rx
-            .try_iter()
-            .for_each(|(image, index, zoom_index)| {
-                if zoom_index == self.zoom_index {
-                    self.maps_images
-                        .insert((index, self.zoom_index), AsyncStatus::Ready(image));
+    // Item to craft removed or crafted
+    fn check_for_removed_item(&mut self, ctx: &Context) {
+        let removed_items = self.remove_item_rx.try_iter().collect_vec();
+
+        removed_items
+            .into_iter()
+            .for_each(|(item, quantity_to_remove, crafted)| {
+                if crafted {
+                    self.craft(ctx, item, quantity_to_remove);
+                    return;
                 }
+
+                let Some((value, _)) = self.items.get(&item) else {
+                    return;
+                };
+
+                // Cant remove more than what we have
+                let quantity_to_remove = cmp::min(quantity_to_remove, *value);
+
+                // Lost when the item leaves the wish list
+                let project_id = self.items_project.get(&item).copied();
+                let delta = self.change_wish_list(ctx, &item, -quantity_to_remove);
+                self.write_journal(
+                    ctx,
+                    vec![(item, JournalTarget::WishList, delta, project_id)],
+                    JournalReason::Remove,
+                );
             });
     }
 
-    // New item to craft / to have
-    fn check_for_new_items(&mut self, ctx: &Context) {
-        self.item_rx.try_iter().for_each(|(item, quantity)| {
-            let new_quantity = if let Some((item_value, _)) = self.items.get_mut(&item) {
-                *item_value += quantity;
-                *item_value
-            } else {
-                self.items_images.entry(item.clone()).or_insert_with(|| {
-                    Self::load_item_image(self.item_image_tx.clone(), ctx.clone(), item.clone());
-                    AsyncStatus::Loading
-                });
+    // Queued after the previous writes so the saved inventory is up to date
+    fn craft(&mut self, ctx: &Context, item: Item, quantity: Quantity) {
+        let recipe_graph = self.recipe_graph.clone();
+        let profile_id = self.profile.id;
 
-                self.items
-                    .insert(item.clone(), (quantity as _, AsyncStatus::Loading));
-                Self::load_recipe_of_item(
-                    self.item_ingredients_tx.clone(),
-                    self.pool.clone(),
-                    item.clone(),
-                    quantity as _,
-                );
+        self.database_worker
+            .query(ctx, self.craft_tx.clone(), move |connection| {
+                (
+                    profile_id,
+                    craft(connection, &recipe_graph, profile_id, &item, quantity),
+                )
+            });
+    }
 
-                quantity
-            };
+    fn check_for_crafts(&mut self, ctx: &Context) {
+        let current_profile_id = self.profile.id;
+        let crafts = self.craft_rx.try_iter().collect_vec();
 
-            use crate::database::schema::user_items;
-            use diesel::prelude::*;
+        crafts
+            .into_iter()
+            .filter(|(profile_id, _)| *profile_id == current_profile_id)
+            .for_each(|(_, result)| match result {
+                Ok(craft) => self.apply_craft(ctx, craft),
+                Err(error) => self.craft_message = Some(error.to_string()),
+            });
+    }
+
+    fn apply_craft(&mut self, ctx: &Context, craft: Craft) {
+        // Already saved, only the state of the window is changed
+        let wished = self
+            .items
+            .get(&craft.item)
+            .map(|(quantity, _)| *quantity)
+            .unwrap_or(0);
+        let project_id = self.items_project.get(&craft.item).copied();
+        self.set_wish_list_quantity(ctx, &craft.item, wished - craft.quantity);
+        let mut changes = vec![(
+            craft.item.clone(),
+            JournalTarget::WishList,
+            -craft.quantity,
+            project_id,
+        )];
+
+        craft.consumed.iter().for_each(|(ingredient, quantity)| {
+            let in_stock = *self.ingredients_quantity.get(ingredient).unwrap_or(&0);
+            self.set_inventory_quantity(ingredient, in_stock - quantity);
+            changes.push((
+                ingredient.clone(),
+                JournalTarget::Inventory,
+                -quantity,
+                None,
+            ));
+        });
+
+        self.update_calculated_inventory();
+        self.write_journal(ctx, changes, JournalReason::Craft);
+
+        let intermediates = if craft.intermediates.is_empty() {
+            String::new()
+        } else {
+            format!(
+                ", with {}",
+                craft
+                    .intermediates
+                    .iter()
+                    .map(|(item, quantity)| format!("{quantity} {}", item.name))
+                    .join(", ")
+            )
+        };
+        self.craft_message = Some(format!(
+            "Crafted {} {}{intermediates}",
+            craft.quantity, craft.item.name
+        ));
+    }
+
+    // Returns the quantity really added or removed
+    fn change_wish_list(&mut self, ctx: &Context, item: &Item, delta: Quantity) -> Quantity {
+        let old_quantity = self
+            .items
+            .get(item)
+            .map(|(quantity, _)| *quantity)
+            .unwrap_or(0);
+        let new_quantity = cmp::max(old_quantity.saturating_add(delta), 0);
+
+        self.set_wish_list_quantity(ctx, item, new_quantity);
+        self.save_wish_list_quantity(ctx, item, new_quantity);
+        self.update_calculated_inventory();
+
+        new_quantity - old_quantity
+    }
+
+    fn set_wish_list_quantity(&mut self, ctx: &Context, item: &Item, quantity: Quantity) {
+        if quantity == 0 {
+            self.items.remove(item);
+            self.items_project.remove(item);
+        } else if let Some((item_value, _)) = self.items.get_mut(item) {
+            *item_value = quantity;
+        } else {
+            self.items_images.entry(item.clone()).or_insert_with(|| {
+                Self::load_item_image(self.item_image_tx.clone(), ctx.clone(), item.clone());
+                AsyncStatus::Loading
+            });
+
+            // New items go to the project shown, unless they already have one
+            if let Some(project_id) = self.item_filter.project_id {
+                self.items_project.entry(item.clone()).or_insert(project_id);
+            }
+
+            self.items
+                .insert(item.clone(), (quantity, AsyncStatus::Loading));
+            Self::load_recipe_of_item(
+                self.item_ingredients_tx.clone(),
+                self.pool.clone(),
+                self.recipe_graph.clone(),
+                item.clone(),
+                quantity,
+            );
+        }
+    }
 
-            let pool = self.pool.clone();
+    fn save_wish_list_quantity(&self, ctx: &Context, item: &Item, quantity: Quantity) {
+        use crate::database::schema::user_items;
+        use diesel::prelude::*;
 
-            tokio::spawn(async move {
-                let mut connection = pool.get().unwrap();
-                let user_item = UserItem::new(item.id, new_quantity);
+        let user_item = UserItem::new(
+            item.id,
+            quantity,
+            self.profile.id,
+            self.items_project.get(item).copied(),
+        );
 
+        self.save(ctx, "Wish list", move |connection| {
+            if user_item.quantity == 0 {
+                delete(user_items::table)
+                    .filter(user_items::profile_id.eq(user_item.profile_id))
+                    .filter(user_items::item_id.eq(user_item.item_id))
+                    .execute(connection)?;
+            } else {
                 insert_into(user_items::table)
                     .values(&user_item)
-                    .on_conflict(user_items::item_id)
+                    .on_conflict((user_items::profile_id, user_items::item_id))
                     .do_update()
-                    .set(&user_item)
-                    .execute(&mut connection)
-                    .unwrap();
-            });
+                    .set(user_items::quantity.eq(user_item.quantity))
+                    .execute(connection)?;
+            }
+            Ok(())
         });
     }
 
-    // Item to craft removed or crafted
-    fn check_for_removed_item(&mut self) {
-        self.remove_item_rx
-            .try_iter()
-            .for_each(|(item, quantity_to_remove, crafted)| {
-                let (value, _) = self.items.get_mut(&item).unwrap();
+    // Returns the quantity really added or removed
+    fn change_inventory(&mut self, ctx: &Context, item: &Item, delta: Quantity) -> Quantity {
+        let old_quantity = *self.ingredients_quantity.get(item).unwrap_or(&0);
+        let new_quantity = cmp::max(old_quantity.saturating_add(delta), 0);
 
-                // Cant remove more than what we have
-                let quantity_to_remove = cmp::min(quantity_to_remove, *value);
+        self.set_inventory_quantity(item, new_quantity);
+        self.save_inventory_quantity(ctx, item, new_quantity);
 
-                *value -= quantity_to_remove;
+        new_quantity - old_quantity
+    }
 
-                if crafted {
-                    let mut connection = self.pool.get().unwrap();
-                    let mut to_remove = item.get_recipe(&quantity_to_remove, &mut connection);
-                    let mut database_update = ItemList::new();
-
-                    while !to_remove.is_empty() {
-                        let (current_item_to_remove, mut current_quantity_to_remove) =
-                            to_remove.pop_first().unwrap();
-
-                        self.ingredients_quantity
-                            .entry(current_item_to_remove.clone())
-                            .and_modify(|quantity| {
-                                let to_remove = cmp::min(*quantity, current_quantity_to_remove);
-                                *quantity -= to_remove;
-                                database_update.set_item(&current_item_to_remove, quantity);
-                                current_quantity_to_remove -= to_remove;
-                            });
-
-                        if current_quantity_to_remove > 0
-                            && current_item_to_remove.has_recipe(&mut connection)
-                        {
-                            to_remove.add_items(
-                                &current_item_to_remove
-                                    .get_recipe(&current_quantity_to_remove, &mut connection),
-                            );
-                        }
-                    }
+    fn set_inventory_quantity(&mut self, item: &Item, quantity: Quantity) {
+        if quantity <= 0 {
+            self.ingredients_quantity.remove(item);
+        } else {
+            self.ingredients_quantity.set_item(item, &quantity);
+        }
+    }
 
-                    self.calculated_inventory =
-                        Self::get_calculated_inventory(&self.ingredients_quantity, &mut connection);
-
-                    // Update database
-                    let pool = self.pool.clone();
-                    tokio::spawn(async move {
-                        use crate::database::schema::user_ingredients;
-                        use diesel::prelude::*;
-
-                        let mut connection = pool.get().unwrap();
-                        database_update.iter().for_each(|(item, quantity)| {
-                            if *quantity == 0 {
-                                delete(user_ingredients::table)
-                                    .filter(user_ingredients::item_id.eq(item.id))
-                                    .execute(&mut connection)
-                                    .unwrap();
-                            } else {
-                                let user_ingredient = UserIngredient::new(item.id, *quantity);
-                                update(user_ingredients::table)
-                                    .filter(user_ingredients::item_id.eq(user_ingredient.item_id))
-                                    .set(&user_ingredient)
-                                    .execute(&mut connection)
-                                    .unwrap();
-                            }
-                        });
-                    });
-                }
+    fn save_inventory_quantity(&self, ctx: &Context, item: &Item, quantity: Quantity) {
+        use crate::database::schema::user_ingredients;
+        use diesel::prelude::*;
+
+        let user_ingredient = UserIngredient::new(item.id, quantity, self.profile.id);
+
+        self.save(ctx, "Inventory", move |connection| {
+            if user_ingredient.quantity == 0 {
+                delete(user_ingredients::table)
+                    .filter(user_ingredients::profile_id.eq(user_ingredient.profile_id))
+                    .filter(user_ingredients::item_id.eq(user_ingredient.item_id))
+                    .execute(connection)?;
+            } else {
+                insert_into(user_ingredients::table)
+                    .values(&user_ingredient)
+                    .on_conflict((user_ingredients::profile_id, user_ingredients::item_id))
+                    .do_update()
+                    .set(&user_ingredient)
+                    .execute(connection)?;
+            }
+            Ok(())
+        });
+    }
 
-                let pool = self.pool.clone();
-                let item_id = item.id;
+    // Saves the changes of one action so they can be undone together
+    fn write_journal(&self, ctx: &Context, changes: Vec<JournalChange>, reason: JournalReason) {
+        let changes = changes
+            .into_iter()
+            .filter(|(_, _, delta, _)| *delta != 0)
+            .collect_vec();
 
-                if *value == 0 {
-                    self.items.remove(&item);
+        if changes.is_empty() {
+            return;
+        }
 
-                    tokio::spawn(async move {
-                        use crate::database::schema::user_items;
-                        use diesel::prelude::*;
+        let profile_id = self.profile.id;
 
-                        let mut connection = pool.get().unwrap();
+        self.database_worker
+            .query(ctx, self.history_tx.clone(), move |connection| {
+                use crate::database::functions::nextval;
+                use crate::database::schema::journal_entries;
+                use diesel::prelude::*;
 
-                        delete(user_items::table)
-                            .filter(user_items::item_id.eq(item_id))
-                            .execute(&mut connection)
-                            .unwrap();
-                    });
-                } else {
-                    let user_item = UserItem::new(item_id, *value);
-                    tokio::spawn(async move {
-                        use crate::database::schema::user_items;
-                        use diesel::prelude::*;
-
-                        let mut connection = pool.get().unwrap();
-
-                        update(user_items::table)
-                            .filter(user_items::item_id.eq(user_item.item_id))
-                            .set(&user_item)
-                            .execute(&mut connection)
-                            .unwrap();
-                    });
+                let written = connection.transaction(|connection| {
+                    // Undone changes can't be redone after a new change
+                    delete(journal_entries::table)
+                        .filter(journal_entries::profile_id.eq(profile_id))
+                        .filter(journal_entries::undone.eq(true))
+                        .execute(connection)?;
+
+                    let batch_id = diesel::select(nextval("journal_batch_id_seq"))
+                        .get_result::<i64>(connection)?;
+
+                    let entries = changes
+                        .iter()
+                        .map(|(item, target, delta, project_id)| {
+                            NewJournalEntry::new(
+                                profile_id,
+                                batch_id,
+                                item.id,
+                                target.as_str().to_owned(),
+                                *delta,
+                                reason.as_str().to_owned(),
+                                *project_id,
+                            )
+                        })
+                        .collect_vec();
+
+                    insert_into(journal_entries::table)
+                        .values(&entries)
+                        .execute(connection)
+                });
+
+                (
+                    profile_id,
+                    written.and_then(|_| Self::load_history(profile_id, connection)),
+                )
+            });
+    }
+
+    fn load_history(profile_id: i32, connection: &mut PgConnection) -> QueryResult<History> {
+        use crate::database::schema::{items, journal_entries};
+        use diesel::prelude::*;
+
+        journal_entries::table
+            .inner_join(items::table)
+            .filter(journal_entries::profile_id.eq(profile_id))
+            .order(journal_entries::id.desc())
+            .limit(Self::HISTORY_SIZE)
+            .load(connection)
+    }
+
+    // Undo puts back the last batch still done, redo the first batch undone
+    fn undo_or_redo(&mut self, ctx: &Context, action: HistoryAction) {
+        use crate::database::schema::{items, journal_entries};
+        use diesel::dsl;
+        use diesel::prelude::*;
+
+        let span = trace_span!("undo_or_redo");
+        let _guard = span.enter();
+
+        let undo = action == HistoryAction::Undo;
+        let profile_id = self.profile.id;
+
+        // Queued after the journal writes so the last action can be undone right away
+        let job = move |connection: &mut PgConnection| {
+            let profile_entries = journal_entries::table
+                .filter(journal_entries::profile_id.eq(profile_id))
+                .filter(journal_entries::undone.eq(!undo));
+
+            let batch_id = if undo {
+                profile_entries
+                    .select(dsl::max(journal_entries::batch_id))
+                    .first::<Option<i64>>(connection)?
+            } else {
+                profile_entries
+                    .select(dsl::min(journal_entries::batch_id))
+                    .first::<Option<i64>>(connection)?
+            };
+
+            let Some(batch_id) = batch_id else {
+                return Ok(None);
+            };
+
+            let entries: Vec<(JournalEntry, Item)> = journal_entries::table
+                .inner_join(items::table)
+                .filter(journal_entries::profile_id.eq(profile_id))
+                .filter(journal_entries::batch_id.eq(batch_id))
+                .load(connection)?;
+
+            update(journal_entries::table)
+                .filter(journal_entries::profile_id.eq(profile_id))
+                .filter(journal_entries::batch_id.eq(batch_id))
+                .set(journal_entries::undone.eq(undo))
+                .execute(connection)?;
+
+            Ok(Some((entries, Self::load_history(profile_id, connection)?)))
+        };
+
+        self.database_worker
+            .query(ctx, self.journal_batch_tx.clone(), move |connection| {
+                (profile_id, undo, job(connection))
+            });
+    }
+
+    // Applies a batch undone or redone in the database
+    fn apply_journal_batch(&mut self, ctx: &Context, undo: bool, batch: JournalBatch) {
+        let (entries, history) = batch;
+
+        entries.iter().for_each(|(entry, item)| {
+            let delta = if undo { -entry.delta } else { entry.delta };
+
+            match entry.target.parse() {
+                Ok(JournalTarget::Inventory) => {
+                    self.change_inventory(ctx, item, delta);
+                }
+                Ok(JournalTarget::WishList) => {
+                    // An item put back in the wish list goes back to its project
+                    if let Some(project_id) =
+                        entry.project_id.filter(|_| !self.items.contains_key(item))
+                    {
+                        self.items_project.insert(item.clone(), project_id);
+                    }
+                    self.change_wish_list(ctx, item, delta);
                 }
+                Err(error) => warn!("{error}"),
+            }
+        });
+
+        self.update_calculated_inventory();
+        self.history = history;
+    }
+
+    fn check_for_history(&mut self, ctx: &Context) {
+        self.history_rx
+            .try_iter()
+            .filter(|(profile_id, _)| *profile_id == self.profile.id)
+            .for_each(|(_, history)| match history {
+                Ok(history) => self.history = history,
+                Err(error) => warn!("Journal not saved: {error}"),
+            });
+
+        let mut actions = self.history_action_rx.try_iter().collect_vec();
+
+        // Text fields have their own undo
+        if ctx.memory(|memory| memory.focused().is_none()) {
+            ctx.input(|input| {
+                if input.modifiers.command && input.key_pressed(Key::Z) {
+                    actions.push(HistoryAction::Undo);
+                } else if input.modifiers.command && input.key_pressed(Key::Y) {
+                    actions.push(HistoryAction::Redo);
+                }
+            });
+        }
+
+        actions
+            .into_iter()
+            .for_each(|action| self.undo_or_redo(ctx, action));
+
+        let current_profile_id = self.profile.id;
+        let batches = self.journal_batch_rx.try_iter().collect_vec();
+        batches
+            .into_iter()
+            .filter(|(profile_id, _, _)| *profile_id == current_profile_id)
+            .for_each(|(_, undo, batch)| match batch {
+                Ok(Some(batch)) => self.apply_journal_batch(ctx, undo, batch),
+                Ok(None) => {}
+                Err(error) => warn!("Journal not undone: {error}"),
             });
     }
 
@@ -820,52 +1841,207 @@ impl MainWindow {
     }
 
     // New item in invetory
-    fn check_for_new_ingredient_in_inventory(&mut self) {
-        self.new_ingredient_rx
-            .try_iter()
-            .for_each(|(item, quantity)| {
-                let user_ingredient = UserIngredient::new(item.id, 0);
-
-                let quantity = *self
-                    .ingredients_quantity
-                    .entry(item)
-                    .and_modify(|old_quantity| {
-                        *old_quantity = cmp::max(*old_quantity as isize + quantity, 0) as _;
-                    })
-                    .or_insert(cmp::max(quantity, 0) as _) as i16;
+    fn check_for_new_ingredient_in_inventory(&mut self, ctx: &Context) {
+        let new_ingredients = self.new_ingredient_rx.try_iter().collect_vec();
 
-                let pool = self.pool.clone();
+        new_ingredients.into_iter().for_each(|(item, quantity)| {
+            let delta = self.change_inventory(ctx, &item, quantity);
 
-                self.calculated_inventory = Self::get_calculated_inventory(
-                    &self.ingredients_quantity,
-                    &mut pool.get().unwrap(),
-                );
+            self.update_calculated_inventory();
+
+            let reason = if delta > 0 && self.active_session.is_some() {
+                self.add_session_loot(ctx, &item, delta);
+                JournalReason::Loot
+            } else {
+                JournalReason::Manual
+            };
+
+            self.write_journal(
+                ctx,
+                vec![(item, JournalTarget::Inventory, delta, None)],
+                reason,
+            );
+        });
+    }
+
+    // Sessions started, stopped and kills counted in the farming tab
+    fn check_for_farming_actions(&mut self, ctx: &Context) {
+        let actions = self.farming_action_rx.try_iter().collect_vec();
+
+        actions.into_iter().for_each(|action| match action {
+            FarmingAction::Start(sub_area) => self.start_farming_session(ctx, sub_area),
+            FarmingAction::Kill(monster, delta) => self.add_session_kills(ctx, monster, delta),
+            FarmingAction::Stop => self.stop_farming_session(ctx),
+        });
+
+        let new_sessions = self.new_session_rx.try_iter().collect_vec();
+        new_sessions
+            .into_iter()
+            .for_each(|(session, sub_area)| match session {
+                Ok(session) if session.profile_id == self.profile.id => {
+                    if self.active_session.is_some() {
+                        self.stop_farming_session(ctx);
+                    }
 
-                let user_ingredient = UserIngredient {
-                    quantity,
-                    ..user_ingredient
+                    self.active_session = Some(ActiveSession {
+                        session,
+                        sub_area,
+                        kills: BTreeMap::new(),
+                        loot: ItemList::new(),
+                    });
+                }
+                Ok(_) => {}
+                Err(error) => warn!("Farming session not started: {error}"),
+            });
+
+        let stopped_sessions = self.drop_rates_rx.try_iter().collect_vec();
+        stopped_sessions
+            .into_iter()
+            .for_each(|(active_session, drop_rates)| match drop_rates {
+                Ok(drop_rates) => self.drop_rates = drop_rates,
+                Err(error) => {
+                    warn!("Farming session not stopped: {error}");
+                    self.save_error = Some(format!("Farming session not stopped: {error}"));
+
+                    // Still running in the database
+                    if self.active_session.is_none()
+                        && active_session.session.profile_id == self.profile.id
+                    {
+                        self.active_session = Some(active_session);
+                    }
+                }
+            });
+
+        let current_session_id = self
+            .active_session
+            .as_ref()
+            .map(|active_session| active_session.session.id);
+        let counts = self.session_count_rx.try_iter().collect_vec();
+        counts
+            .into_iter()
+            .filter(|(session_id, _, _)| Some(*session_id) == current_session_id)
+            .for_each(|(_, count, result)| {
+                let (Err(error), Some(active_session)) = (result, &mut self.active_session) else {
+                    return;
                 };
 
-                use crate::database::schema::user_ingredients;
-                use diesel::prelude::*;
+                warn!("Farming session not saved: {error}");
+                self.save_error = Some(format!("Farming session not saved: {error}"));
 
-                tokio::spawn(async move {
-                    let mut connection = pool.get().unwrap();
-                    if user_ingredient.quantity == 0 {
-                        delete(user_ingredients::table)
-                            .filter(user_ingredients::item_id.eq(user_ingredient.item_id))
-                            .execute(&mut connection)
-                            .unwrap();
-                    } else {
-                        insert_into(user_ingredients::table)
-                            .values(&user_ingredient)
-                            .on_conflict(user_ingredients::item_id)
-                            .do_update()
-                            .set(&user_ingredient)
-                            .execute(&mut connection)
-                            .unwrap();
+                match count {
+                    SessionCount::Kills(monster, 0) => {
+                        active_session.kills.remove(&monster);
                     }
-                });
+                    SessionCount::Kills(monster, kills) => {
+                        active_session.kills.insert(monster, kills);
+                    }
+                    SessionCount::Loot(item, 0) => {
+                        active_session.loot.remove(&item);
+                    }
+                    SessionCount::Loot(item, quantity) => {
+                        active_session.loot.set_item(&item, &quantity);
+                    }
+                }
+            });
+    }
+
+    fn start_farming_session(&mut self, ctx: &Context, sub_area: Option<SubArea>) {
+        use crate::database::schema::farming_sessions;
+        use diesel::prelude::*;
+
+        if self.active_session.is_some() {
+            self.stop_farming_session(ctx);
+        }
+
+        let new_session = NewFarmingSession::new(
+            self.profile.id,
+            sub_area.as_ref().map(|sub_area| sub_area.id),
+            Local::now().naive_local(),
+        );
+
+        self.database_worker
+            .query(ctx, self.new_session_tx.clone(), move |connection| {
+                let session = insert_into(farming_sessions::table)
+                    .values(new_session)
+                    .returning(FarmingSession::as_returning())
+                    .get_result(connection);
+                (session, sub_area)
+            });
+    }
+
+    // The drop rates are computed again with this session
+    fn stop_farming_session(&mut self, ctx: &Context) {
+        use crate::database::schema::farming_sessions;
+        use diesel::prelude::*;
+
+        let Some(active_session) = self.active_session.take() else {
+            return;
+        };
+
+        let session_id = active_session.session.id;
+        let stopped_at = Local::now().naive_local();
+
+        self.database_worker
+            .query(ctx, self.drop_rates_tx.clone(), move |connection| {
+                let stopped = update(farming_sessions::table.find(session_id))
+                    .set(farming_sessions::stopped_at.eq(stopped_at))
+                    .execute(connection);
+
+                (active_session, stopped.map(|_| DropRates::load(connection)))
+            });
+    }
+
+    fn add_session_kills(&mut self, ctx: &Context, monster: Monster, delta: i32) {
+        use crate::database::schema::session_kills;
+        use diesel::prelude::*;
+
+        let Some(active_session) = &mut self.active_session else {
+            return;
+        };
+
+        let kills = active_session.kills.entry(monster.clone()).or_default();
+        let previous = SessionCount::Kills(monster.clone(), *kills);
+        *kills = cmp::max(kills.saturating_add(delta), 0);
+        let session_kill = SessionKill::new(active_session.session.id, monster.id, *kills);
+
+        self.database_worker
+            .query(ctx, self.session_count_tx.clone(), move |connection| {
+                let saved = insert_into(session_kills::table)
+                    .values(&session_kill)
+                    .on_conflict((session_kills::session_id, session_kills::monster_id))
+                    .do_update()
+                    .set(session_kills::kills.eq(session_kill.kills))
+                    .execute(connection);
+                (session_kill.session_id, previous, saved.map(|_| ()))
+            });
+    }
+
+    fn add_session_loot(&mut self, ctx: &Context, item: &Item, quantity: Quantity) {
+        use crate::database::schema::session_loot;
+        use diesel::prelude::*;
+
+        let Some(active_session) = &mut self.active_session else {
+            return;
+        };
+
+        let previous =
+            SessionCount::Loot(item.clone(), *active_session.loot.get(item).unwrap_or(&0));
+        active_session.loot.add_item(item, &quantity);
+        let session_loot = SessionLoot::new(
+            active_session.session.id,
+            item.id,
+            active_session.loot[item],
+        );
+
+        self.database_worker
+            .query(ctx, self.session_count_tx.clone(), move |connection| {
+                let saved = insert_into(session_loot::table)
+                    .values(&session_loot)
+                    .on_conflict((session_loot::session_id, session_loot::item_id))
+                    .do_update()
+                    .set(session_loot::quantity.eq(session_loot.quantity))
+                    .execute(connection);
+                (session_loot.session_id, previous, saved.map(|_| ()))
             });
     }
 
@@ -892,10 +2068,11 @@ impl MainWindow {
     fn load_recipe_of_item(
         tx: Sender<Ingredients>,
         pool: Pool<ConnectionManager<PgConnection>>,
+        recipe_graph: Arc<RecipeGraph>,
         item: Item,
-        quantity: i16,
+        quantity: Quantity,
     ) {
-        tokio::spawn(async move {
+        tokio::task::spawn_blocking(move || {
             use crate::database::schema::*;
             use diesel::prelude::*;
 
@@ -903,7 +2080,7 @@ impl MainWindow {
 
             let mut result_hash_map = HashMap::new();
 
-            let (base_ingredients, ingredients_steps) = item.get_full_recipe(&1, &mut connection);
+            let (base_ingredients, ingredients_steps) = recipe_graph.get_full_recipe(&item, &1);
 
             base_ingredients.iter().for_each(|(ingredient, quantity)| {
                 let result: Vec<(SubArea, MonsterSubArea, Monster, Drop, Item)> = sub_areas::table
@@ -946,12 +2123,159 @@ impl MainWindow {
         });
     }
 
-    fn get_calculated_inventory(inventory: &ItemList, connection: &mut PgConnection) -> ItemList {
+    fn update_calculated_inventory(&mut self) {
+        let wish_list = ItemList::with_items(
+            self.items
+                .iter()
+                .filter(|(item, _)| self.item_filter.matches(item, &self.items_project))
+                .map(|(item, (quantity, _))| (item.clone(), *quantity))
+                .collect(),
+        );
+
+        self.calculated_inventory =
+            Self::get_calculated_inventory(&self.ingredients_quantity, &self.recipe_graph);
+        self.craft_plan =
+            CraftPlan::new(&self.recipe_graph, &wish_list, &self.ingredients_quantity);
+        self.craft_cost = CraftCost::new(&self.recipe_graph, &self.craft_plan, &self.prices);
+
+        let projects = self
+            .projects
+            .iter()
+            .map(|project| {
+                let wish_list = ItemList::with_items(
+                    self.items
+                        .iter()
+                        .filter(|(item, _)| self.items_project.get(item) == Some(&project.id))
+                        .map(|(item, (quantity, _))| (item.clone(), *quantity))
+                        .collect(),
+                );
+                (project.id, wish_list)
+            })
+            .collect_vec();
+        self.projects_progress =
+            projects_progress(&self.recipe_graph, &projects, &self.ingredients_quantity);
+    }
+
+    // Projects created, reordered or deleted and items moved in the wish list tab
+    fn check_for_project_actions(&mut self, ctx: &Context) {
+        use crate::database::schema::{projects, user_items};
+        use diesel::prelude::*;
+
+        let new_projects = self.new_project_rx.try_iter().collect_vec();
+        new_projects.into_iter().for_each(|project| match project {
+            Ok(project) if project.profile_id == self.profile.id => self.projects.push(project),
+            Ok(_) => {}
+            Err(error) => warn!("Project not created: {error}"),
+        });
+
+        let actions = self.project_action_rx.try_iter().collect_vec();
+        if actions.is_empty() {
+            return;
+        }
+
+        let profile_id = self.profile.id;
+
+        actions.into_iter().for_each(|action| match action {
+            ProjectAction::Create(name, deadline) => {
+                let priority = self
+                    .projects
+                    .iter()
+                    .map(|project| project.priority + 1)
+                    .max()
+                    .unwrap_or(0);
+
+                self.database_worker
+                    .query(ctx, self.new_project_tx.clone(), move |connection| {
+                        insert_into(projects::table)
+                            .values(NewProject::new(profile_id, name, priority, deadline))
+                            .returning(Project::as_returning())
+                            .get_result(connection)
+                    });
+            }
+            ProjectAction::Delete(project_id) => {
+                self.projects.retain(|project| project.id != project_id);
+                self.items_project.retain(|_, id| *id != project_id);
+                if self.item_filter.project_id == Some(project_id) {
+                    self.item_filter.project_id = None;
+                }
+
+                self.save(ctx, "Project deletion", move |connection| {
+                    delete(projects::table.find(project_id))
+                        .execute(connection)
+                        .map(|_| ())
+                });
+            }
+            ProjectAction::Raise(project_id) | ProjectAction::Lower(project_id) => {
+                let Some(index) = self
+                    .projects
+                    .iter()
+                    .position(|project| project.id == project_id)
+                else {
+                    return;
+                };
+                let other_index = if matches!(action, ProjectAction::Raise(_)) {
+                    index.checked_sub(1)
+                } else {
+                    Some(index + 1).filter(|other_index| *other_index < self.projects.len())
+                };
+                let Some(other_index) = other_index else {
+                    return;
+                };
+
+                // Swapped positions, then priorities follow the order again
+                self.projects.swap(index, other_index);
+                let (first, second) = (cmp::min(index, other_index), cmp::max(index, other_index));
+                let priority = self.projects[second].priority;
+                self.projects[second].priority = self.projects[first].priority;
+                self.projects[first].priority = priority;
+
+                let changes = [&self.projects[first], &self.projects[second]]
+                    .map(|project| (project.id, project.priority));
+                self.save(ctx, "Project order", move |connection| {
+                    connection.transaction(|connection| {
+                        changes.iter().try_for_each(|(id, priority)| {
+                            update(projects::table.find(id))
+                                .set(projects::priority.eq(priority))
+                                .execute(connection)
+                                .map(|_| ())
+                        })
+                    })
+                });
+            }
+            ProjectAction::MoveItem(item, project_id) => {
+                match project_id {
+                    Some(project_id) => self.items_project.insert(item.clone(), project_id),
+                    None => self.items_project.remove(&item),
+                };
+
+                self.save(ctx, "Item project", move |connection| {
+                    update(user_items::table)
+                        .filter(user_items::profile_id.eq(profile_id))
+                        .filter(user_items::item_id.eq(item.id))
+                        .set(user_items::project_id.eq(project_id))
+                        .execute(connection)
+                        .map(|_| ())
+                });
+            }
+        });
+
+        self.update_calculated_inventory();
+    }
+
+    // Prices changed in the prices tab
+    fn check_for_prices(&mut self) {
+        if let Some(prices) = self.prices_rx.try_iter().last() {
+            self.prices = prices;
+            self.update_calculated_inventory();
+        }
+    }
+
+    fn get_calculated_inventory(inventory: &ItemList, recipe_graph: &RecipeGraph) -> ItemList {
         let mut calculated_inventory = ItemList::new();
         inventory.iter().for_each(|(ingredient, quantity)| {
             calculated_inventory.add_item(ingredient, quantity);
-            if ingredient.has_recipe(connection) {
-                let (base_ingredients, steps) = ingredient.get_full_recipe(quantity, connection);
+            if recipe_graph.is_craftable(ingredient) {
+                let (base_ingredients, steps) = recipe_graph.get_full_recipe(ingredient, quantity);
                 calculated_inventory.add_items(&base_ingredients);
                 steps.iter().for_each(|step_list| {
                     calculated_inventory.add_items(step_list);
@@ -961,38 +2285,6 @@ impl MainWindow {
 
         calculated_inventory
     }
-
-    fn zoom_in(&mut self, pointer_pos: Pos2) {
-        if self.zoom_index > 0 {
-            self.update_zoom(self.zoom_index - 1, pointer_pos);
-        }
-    }
-
-    fn zoom_out(&mut self, pointer_pos: Pos2) {
-        if self.zoom_index < Self::ZOOMS.len() - 1 {
-            self.update_zoom(self.zoom_index + 1, pointer_pos);
-        }
-    }
-
-    fn update_zoom(&mut self, zoom_index: usize, pointer_pos: Pos2) {
-        let old_zoom_index = self.zoom_index;
-
-        self.maps_images.clear();
-        self.zoom_index = zoom_index;
-        self.images_number = Self::image_number_from_zoom(zoom_index);
-
-        self.map_position = pointer_pos
-            + ((self.map_position - pointer_pos) / Self::ZOOMS[old_zoom_index]
-                * Self::ZOOMS[zoom_index]);
-    }
-
-    fn image_number_from_zoom(zoom_index: usize) -> (u8, u8) {
-        let zoom = Self::ZOOMS[zoom_index];
-        (
-            ((Self::FULL_IMAGE_SIZE.x * zoom) / Self::IMAGE_SIZE.x).ceil() as u8,
-            ((Self::FULL_IMAGE_SIZE.y * zoom) / Self::IMAGE_SIZE.y).ceil() as u8,
-        )
-    }
 }
 
 impl eframe::App for MainWindow {
@@ -1001,26 +2293,95 @@ impl eframe::App for MainWindow {
         let _guard = span.enter();
 
         self.check_for_new_items(ctx);
-        self.check_for_removed_item();
+        self.check_for_removed_item(ctx);
         self.check_for_new_item_ingredients(ctx);
-        self.check_for_new_ingredient_in_inventory();
+        self.check_for_new_ingredient_in_inventory(ctx);
+        self.check_for_history(ctx);
+        self.check_for_saves(ctx);
+        self.check_for_prices();
+        self.check_for_project_actions(ctx);
+        self.check_for_profiles(ctx);
+        self.check_for_favorites();
+        self.check_for_crafts(ctx);
+        self.check_for_farming_actions(ctx);
         self.check_for_new_items_images();
         self.check_for_new_monsters_images();
         self.check_for_new_map_images();
 
+        TopBottomPanel::top("profiles").show(ctx, |ui| self.profiles_panel_ui(ui));
+        TopBottomPanel::bottom("status").show(ctx, |ui| self.status_bar_ui(ui));
+
+        let missing_drops = self.missing_drops();
+        if self.favorites_panel {
+            SidePanel::left("favorites")
+                .show(ctx, |ui| self.favorites_panel_ui(ui, &missing_drops));
+        }
+
         let frame = Frame::default().fill(Color32::from_rgb(30, 25, 25));
         CentralPanel::default()
             .frame(frame)
-            .show(ctx, |ui| self.central_panel_ui(ui));
+            .show(ctx, |ui| self.central_panel_ui(ui, &missing_drops));
 
+        let visible_items = self.item_filter.apply(&self.items, &self.items_project);
         self.items_window.show(
             ctx,
             &self.items,
+            &visible_items,
             &self.ingredients_quantity,
             &self.calculated_inventory,
+            &self.craft_plan,
+            &self.craft_cost,
+            &self.prices,
             &self.items_images,
             &self.monsters_images,
-            &self.current_sub_area,
+            &self.current_sub_areas,
+            &self.recipe_graph,
+            &self.history,
+            &self.projects,
+            &self.items_project,
+            &self.projects_progress,
+            &self.active_session,
+            &self.drop_rates,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::MainWindow;
+    use crate::{
+        crafting::recipe_graph::RecipeGraph,
+        database::models::item::ItemList,
+        test_database::{item, TestDatabase},
+    };
+
+    #[test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    fn calculated_inventory_counts_what_crafted_items_contain() {
+        let database = TestDatabase::new();
+        let connection = &mut database.connection();
+        let graph = RecipeGraph::load(connection);
+
+        let (wheat, water, flour, bread) = (
+            item(connection, 1),
+            item(connection, 2),
+            item(connection, 3),
+            item(connection, 4),
+        );
+
+        let inventory =
+            ItemList::with_items(BTreeMap::from([(bread.clone(), 1), (wheat.clone(), 2)]));
+
+        assert_eq!(
+            MainWindow::get_calculated_inventory(&inventory, &graph),
+            ItemList::with_items(BTreeMap::from([
+                (bread, 1),
+                (wheat, 8),
+                (water, 1),
+                (flour, 2)
+            ]))
         );
     }
 }
@@ -0,0 +1,219 @@
+use diesel::{delete, insert_into};
+use egui::{Grid, Pos2, Rect, TextEdit, Ui, Vec2};
+use itertools::Itertools;
+use tracing::warn;
+
+use crate::{
+    database::models::{
+        favorite::{Favorite, NewFavorite},
+        map::Map,
+        sub_area::SubArea,
+    },
+    windows::main_window::{MainWindow, MissingDrops},
+};
+
+impl MainWindow {
+    // Full map point at zoom 1 in the center of the view
+    fn view_center(&self) -> Pos2 {
+        ((self.map_viewport.center() - self.map_position) / self.scale).to_pos2()
+    }
+
+    fn show_view(&mut self, center: Pos2, scale: f32) {
+        let viewport_center = self.map_viewport.center();
+        self.set_scale(scale, viewport_center);
+        self.target_scale = scale;
+        self.map_position = viewport_center - center.to_vec2() * scale;
+    }
+
+    // Whether one of the maps is centered in a part of the full map at zoom 1
+    pub(crate) fn maps_in_view(&self, maps: &[Map], view: Rect) -> bool {
+        let cell_size = self.map_cell_size() / self.scale;
+
+        maps.iter().any(|map| {
+            let index = Vec2::new(
+                (map.x - self.map_min_max.x_min) as f32 + 0.5,
+                (map.y - self.map_min_max.y_min) as f32 + 0.5,
+            );
+            view.contains(Self::MAPS_RECT.min + index * cell_size)
+        })
+    }
+
+    fn selected_sub_area(&self) -> Option<&SubArea> {
+        let (x_index, y_index) = self.clicked_map?;
+        self.sub_area_at(
+            x_index as i16 + self.map_min_max.x_min,
+            y_index as i16 + self.map_min_max.y_min,
+        )
+    }
+
+    pub(crate) fn check_for_favorites(&mut self) {
+        let new_favorites = self.new_favorite_rx.try_iter().collect_vec();
+
+        new_favorites
+            .into_iter()
+            .for_each(|favorite| match favorite {
+                Ok(favorite) if favorite.profile_id == self.profile.id => {
+                    self.favorites.push(favorite);
+                    self.favorites
+                        .sort_by(|left, right| left.name.cmp(&right.name));
+                    self.new_favorite_name.clear();
+                }
+                Ok(_) => {}
+                Err(error) => warn!("Favorite not saved: {error}"),
+            });
+    }
+
+    // Saved zones and views of the profile, with the missing ingredients dropped in each
+    pub(crate) fn favorites_panel_ui(&mut self, ui: &mut Ui, missing_drops: &MissingDrops) {
+        use crate::database::schema::favorites;
+        use diesel::prelude::*;
+
+        ui.heading("Favorites");
+
+        ui.add(TextEdit::singleline(&mut self.new_favorite_name).hint_text("Name"));
+        let name = self.new_favorite_name.trim().to_owned();
+        let selected_sub_area = self.selected_sub_area().cloned();
+
+        let mut new_favorite = None;
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(!name.is_empty(), egui::Button::new("Save view"))
+                .clicked()
+            {
+                let center = self.view_center();
+                new_favorite = Some(NewFavorite::new(
+                    self.profile.id,
+                    name.clone(),
+                    None,
+                    Some(center.x),
+                    Some(center.y),
+                    Some(self.scale),
+                ));
+            }
+
+            if ui
+                .add_enabled(
+                    selected_sub_area.is_some(),
+                    egui::Button::new("Save selected zone"),
+                )
+                .on_hover_text("Double click a zone on the map to select it")
+                .clicked()
+            {
+                if let Some(sub_area) = &selected_sub_area {
+                    let name = if name.is_empty() {
+                        sub_area.name.clone()
+                    } else {
+                        name.clone()
+                    };
+                    new_favorite = Some(NewFavorite::new(
+                        self.profile.id,
+                        name,
+                        Some(sub_area.id),
+                        None,
+                        None,
+                        None,
+                    ));
+                }
+            }
+        });
+
+        if let Some(new_favorite) = new_favorite {
+            self.database_worker
+                .query(ui.ctx(), self.new_favorite_tx.clone(), move |connection| {
+                    insert_into(favorites::table)
+                        .values(new_favorite)
+                        .returning(Favorite::as_returning())
+                        .get_result(connection)
+                });
+        }
+
+        ui.separator();
+
+        let view_size = self.map_viewport.size();
+
+        // Only the sub-areas dropping a missing ingredient are looked at
+        let covered = self
+            .favorites
+            .iter()
+            .map(|favorite| {
+                missing_drops
+                    .items_by_sub_area
+                    .iter()
+                    .filter(|(sub_area, _)| {
+                        match (
+                            favorite.sub_area_id,
+                            favorite.center_x,
+                            favorite.center_y,
+                            favorite.scale,
+                        ) {
+                            (Some(sub_area_id), ..) => sub_area.id == sub_area_id,
+                            (None, Some(x), Some(y), Some(scale)) => {
+                                self.sub_areas.get(*sub_area).is_some_and(|maps| {
+                                    self.maps_in_view(
+                                        maps,
+                                        Rect::from_center_size(Pos2::new(x, y), view_size / scale),
+                                    )
+                                })
+                            }
+                            _ => false,
+                        }
+                    })
+                    .flat_map(|(_, items)| items)
+                    .unique()
+                    .count()
+            })
+            .collect_vec();
+
+        let mut clicked = None;
+        let mut deleted = None;
+        Grid::new("favorites")
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                self.favorites
+                    .iter()
+                    .zip(covered)
+                    .for_each(|(favorite, covered)| {
+                        if ui.link(&favorite.name).clicked() {
+                            clicked = Some(favorite.clone());
+                        }
+                        ui.label(format!("{covered} missing"));
+                        if ui.small_button("x").clicked() {
+                            deleted = Some(favorite.id);
+                        }
+                        ui.end_row();
+                    });
+            });
+
+        if let Some(favorite) = clicked {
+            match (
+                favorite.sub_area_id,
+                favorite.center_x,
+                favorite.center_y,
+                favorite.scale,
+            ) {
+                (Some(sub_area_id), ..) => {
+                    let positions = self
+                        .sub_areas
+                        .iter()
+                        .filter(|(sub_area, _)| sub_area.id == sub_area_id)
+                        .flat_map(|(_, maps)| maps.iter().map(|map| (map.x, map.y)))
+                        .collect_vec();
+                    let selected = positions.first().copied();
+                    self.show_maps(&positions, selected);
+                }
+                (None, Some(x), Some(y), Some(scale)) => self.show_view(Pos2::new(x, y), scale),
+                _ => {}
+            }
+        }
+
+        if let Some(favorite_id) = deleted {
+            self.favorites.retain(|favorite| favorite.id != favorite_id);
+            self.save(ui.ctx(), "Favorite deletion", move |connection| {
+                delete(favorites::table.find(favorite_id))
+                    .execute(connection)
+                    .map(|_| ())
+            });
+        }
+    }
+}
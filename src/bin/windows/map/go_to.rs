@@ -0,0 +1,79 @@
+use egui::{Pos2, Vec2};
+use itertools::Itertools;
+
+use crate::{
+    services::map_search::{find_target, GoToTarget},
+    windows::main_window::MainWindow,
+};
+
+impl MainWindow {
+    // Centers the map on the target with the biggest scale showing it whole, a sub-area or map is
+    // then selected like with a double click
+    pub(crate) fn go_to(&mut self) {
+        let target = find_target(&self.go_to_text, self.sub_areas.keys(), &self.area_names);
+
+        let (positions, selected) = match target {
+            Some(GoToTarget::Coordinates(x, y)) => (vec![(x, y)], Some((x, y))),
+            Some(GoToTarget::SubArea(sub_area)) => {
+                let positions = self.sub_areas[sub_area]
+                    .iter()
+                    .map(|map| (map.x, map.y))
+                    .collect_vec();
+                let selected = positions.first().copied();
+                (positions, selected)
+            }
+            Some(GoToTarget::Area(area_id)) => (
+                self.sub_areas
+                    .iter()
+                    .filter(|(sub_area, _)| sub_area.area_id == area_id)
+                    .flat_map(|(_, maps)| maps.iter().map(|map| (map.x, map.y)))
+                    .collect_vec(),
+                None,
+            ),
+            None => (Vec::new(), None),
+        };
+
+        self.go_to_not_found = !self.show_maps(&positions, selected);
+    }
+
+    // Returns false when there's no map to show
+    pub(crate) fn show_maps(
+        &mut self,
+        positions: &[(i16, i16)],
+        selected: Option<(i16, i16)>,
+    ) -> bool {
+        let (Some((x_min, x_max)), Some((y_min, y_max))) = (
+            positions.iter().map(|(x, _)| *x).minmax().into_option(),
+            positions.iter().map(|(_, y)| *y).minmax().into_option(),
+        ) else {
+            return false;
+        };
+
+        let size_in_maps = Vec2::new((x_max - x_min + 1) as f32, (y_max - y_min + 1) as f32);
+        let unscaled_size = size_in_maps * self.map_cell_size() / self.scale;
+        let available_size = self.map_viewport.size() * 0.8;
+        let scale = (available_size / unscaled_size)
+            .min_elem()
+            .clamp(Self::ZOOMS[0], Self::MAX_SCALE);
+
+        let center = self.map_viewport.center();
+        self.set_scale(scale, center);
+        self.target_scale = scale;
+
+        let center_index = Vec2::new(
+            (x_min + x_max) as f32 / 2f32 - self.map_min_max.x_min as f32 + 0.5,
+            (y_min + y_max) as f32 / 2f32 - self.map_min_max.y_min as f32 + 0.5,
+        );
+        self.map_position = center
+            - (self.map_cell_origin(Pos2::ZERO).to_vec2() + center_index * self.map_cell_size());
+
+        self.clicked_map = selected.map(|(x, y)| {
+            (
+                (x - self.map_min_max.x_min) as f32,
+                (y - self.map_min_max.y_min) as f32,
+            )
+        });
+
+        true
+    }
+}
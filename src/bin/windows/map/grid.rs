@@ -0,0 +1,119 @@
+use egui::{Align2, Color32, FontId, Pos2, Rect, Stroke, Ui, Vec2};
+
+use crate::windows::main_window::MainWindow;
+
+impl MainWindow {
+    // Size of one map on screen at the current zoom
+    pub(crate) fn map_cell_size(&self) -> Vec2 {
+        let zoom = self.scale;
+        Vec2::new(
+            (Self::MAPS_RECT.width() * zoom)
+                / (self.map_min_max.x_max - self.map_min_max.x_min + 1) as f32,
+            (Self::MAPS_RECT.height() * zoom)
+                / (self.map_min_max.y_max - self.map_min_max.y_min + 1) as f32,
+        )
+    }
+
+    // Top left corner of the map at index (0, 0), which is (x_min, y_min) in game
+    pub(crate) fn map_cell_origin(&self, fullmap_position: Pos2) -> Pos2 {
+        fullmap_position + Self::MAPS_RECT.min.to_vec2() * self.scale
+    }
+
+    // pos_on_map is relative to the full map position
+    pub(crate) fn map_index_at(&self, pos_on_map: Pos2) -> (f32, f32) {
+        let index = (pos_on_map - self.map_cell_origin(Pos2::ZERO)) / self.map_cell_size();
+        (index.x.floor(), index.y.floor())
+    }
+
+    pub(crate) fn coordinate_grid_ui(&self, ui: &Ui, fullmap_position: Pos2) {
+        let clip_rect = ui.clip_rect();
+        let rect_size = self.map_cell_size();
+        let origin = self.map_cell_origin(fullmap_position);
+        let columns = self.map_min_max.x_max - self.map_min_max.x_min + 1;
+        let rows = self.map_min_max.y_max - self.map_min_max.y_min + 1;
+        let grid_rect =
+            Rect::from_min_size(origin, Vec2::new(columns as f32, rows as f32) * rect_size);
+
+        // Labels on every map when zoomed in, on multiples of 5 or 10 otherwise
+        let label_step = match rect_size.x.min(rect_size.y) {
+            size if size >= 24f32 => 1,
+            size if size >= 8f32 => 5,
+            _ => 10,
+        };
+        let stroke = Stroke::new(1f32, Color32::from_white_alpha(25));
+        let label_color = Color32::from_white_alpha(160);
+        let font = FontId::monospace(10f32);
+        let painter = ui.painter();
+
+        (0..=columns).for_each(|column| {
+            let x = origin.x + column as f32 * rect_size.x;
+            if !(clip_rect.left()..=clip_rect.right()).contains(&x) {
+                return;
+            }
+
+            painter.vline(x, grid_rect.y_range(), stroke);
+
+            let coordinate = column + self.map_min_max.x_min;
+            if column < columns && coordinate % label_step == 0 {
+                painter.text(
+                    Pos2::new(x + rect_size.x / 2f32, grid_rect.top().max(clip_rect.top())),
+                    Align2::CENTER_TOP,
+                    coordinate.to_string(),
+                    font.clone(),
+                    label_color,
+                );
+            }
+        });
+
+        (0..=rows).for_each(|row| {
+            let y = origin.y + row as f32 * rect_size.y;
+            if !(clip_rect.top()..=clip_rect.bottom()).contains(&y) {
+                return;
+            }
+
+            painter.hline(grid_rect.x_range(), y, stroke);
+
+            let coordinate = row + self.map_min_max.y_min;
+            if row < rows && coordinate % label_step == 0 {
+                painter.text(
+                    Pos2::new(
+                        grid_rect.left().max(clip_rect.left()),
+                        y + rect_size.y / 2f32,
+                    ),
+                    Align2::LEFT_CENTER,
+                    coordinate.to_string(),
+                    font.clone(),
+                    label_color,
+                );
+            }
+        });
+    }
+
+    pub(crate) fn status_bar_ui(&self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let Some((x, y)) = self.hovered_map else {
+                ui.label("Outside the map");
+                return;
+            };
+
+            ui.monospace(format!("[{x}, {y}]"));
+
+            let map = self.sub_areas.iter().find_map(|(sub_area, maps)| {
+                maps.iter()
+                    .find(|map| map.x == x && map.y == y)
+                    .map(|map| (sub_area, map))
+            });
+
+            if let Some((sub_area, map)) = map {
+                ui.separator();
+                ui.label(map.name.as_deref().unwrap_or("Unnamed map"));
+                ui.separator();
+                ui.label(&sub_area.name);
+                if let Some(area_name) = self.area_names.get(&sub_area.area_id) {
+                    ui.separator();
+                    ui.label(area_name);
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,108 @@
+use egui::{Align2, Color32, FontId, Pos2, Rect, Rounding, Ui, Vec2};
+use itertools::Itertools;
+
+use crate::{
+    services::heatmap::{sub_area_scores, HeatmapMetric},
+    windows::main_window::{MainWindow, MissingDrops},
+};
+
+impl MainWindow {
+    // Sub-areas colored from yellow to red by their score, with a legend in the corner
+    pub(crate) fn heatmap_ui(
+        &self,
+        ui: &Ui,
+        fullmap_position: Pos2,
+        missing_drops: &MissingDrops,
+        metric: HeatmapMetric,
+    ) {
+        let missing = missing_drops
+            .missing
+            .iter()
+            .map(|(item, quantity)| (item, *quantity))
+            .collect();
+        let scores = sub_area_scores(&missing_drops.sources, &missing, metric);
+
+        let Some((min, max)) = scores
+            .values()
+            .copied()
+            .minmax_by(f32::total_cmp)
+            .into_option()
+        else {
+            return;
+        };
+
+        scores.iter().for_each(|(sub_area, score)| {
+            let Some(maps) = self.sub_areas.get(sub_area) else {
+                return;
+            };
+
+            let t = if max > min {
+                (score - min) / (max - min)
+            } else {
+                1f32
+            };
+            let color = Self::heatmap_color(t);
+
+            maps.iter().for_each(|map| {
+                self.map_rect_on_pos(ui, map.x as _, map.y as _, fullmap_position, Some(color));
+            });
+        });
+
+        let painter = ui.painter();
+        let font = FontId::proportional(12f32);
+        let bar = Rect::from_min_size(
+            self.map_viewport.left_bottom() + Vec2::new(12f32, -28f32),
+            Vec2::new(160f32, 12f32),
+        );
+
+        painter.rect_filled(
+            bar.expand2(Vec2::new(8f32, 26f32))
+                .translate(Vec2::new(0f32, -12f32)),
+            Rounding::same(4f32),
+            Color32::from_black_alpha(180),
+        );
+        (0..bar.width() as usize).for_each(|x| {
+            let t = x as f32 / bar.width();
+            painter.rect_filled(
+                Rect::from_min_size(
+                    bar.min + Vec2::new(x as f32, 0f32),
+                    Vec2::new(1f32, bar.height()),
+                ),
+                Rounding::ZERO,
+                Self::heatmap_color(t).to_opaque(),
+            );
+        });
+        painter.text(
+            bar.left_top() - Vec2::new(0f32, 4f32),
+            Align2::LEFT_BOTTOM,
+            metric.to_string(),
+            font.clone(),
+            Color32::WHITE,
+        );
+        painter.text(
+            bar.left_bottom() + Vec2::new(0f32, 2f32),
+            Align2::LEFT_TOP,
+            format!("{min:.0}"),
+            font.clone(),
+            Color32::WHITE,
+        );
+        painter.text(
+            bar.right_bottom() + Vec2::new(0f32, 2f32),
+            Align2::RIGHT_TOP,
+            format!("{max:.0}"),
+            font,
+            Color32::WHITE,
+        );
+    }
+
+    // Yellow for 0, red for 1
+    fn heatmap_color(t: f32) -> Color32 {
+        let t = t.clamp(0f32, 1f32);
+        Color32::from_rgba_unmultiplied(
+            (255f32 - 35f32 * t) as u8,
+            (235f32 * (1f32 - t)) as u8,
+            (60f32 * (1f32 - t)) as u8,
+            110,
+        )
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use egui::{Align2, Color32, FontId, Pos2, Rounding, Sense, Stroke, Ui, Vec2};
+use itertools::Itertools;
+
+use crate::windows::main_window::MainWindow;
+
+impl MainWindow {
+    // Icon of the n-th hint category
+    const HINT_COLORS: [Color32; 8] = [
+        Color32::from_rgb(230, 80, 80),
+        Color32::from_rgb(80, 160, 230),
+        Color32::from_rgb(90, 190, 90),
+        Color32::from_rgb(230, 180, 50),
+        Color32::from_rgb(170, 100, 220),
+        Color32::from_rgb(240, 130, 40),
+        Color32::from_rgb(60, 200, 190),
+        Color32::from_rgb(220, 100, 170),
+    ];
+
+    // Shown hint categories, with their icon color
+    pub(crate) fn hints_menu_ui(&mut self, ui: &mut Ui) {
+        ui.menu_button(
+            format!(
+                "Hints ({}/{})",
+                self.hint_categories_shown.len(),
+                self.hint_categories.len()
+            ),
+            |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("All").clicked() {
+                        self.hint_categories_shown = self
+                            .hint_categories
+                            .iter()
+                            .map(|category| category.id)
+                            .collect();
+                    }
+                    if ui.button("None").clicked() {
+                        self.hint_categories_shown.clear();
+                    }
+                });
+
+                self.hint_categories
+                    .iter()
+                    .enumerate()
+                    .for_each(|(index, category)| {
+                        let mut shown = self.hint_categories_shown.contains(&category.id);
+                        ui.horizontal(|ui| {
+                            let (rect, _) =
+                                ui.allocate_exact_size(Vec2::splat(10f32), Sense::hover());
+                            ui.painter().circle_filled(
+                                rect.center(),
+                                5f32,
+                                Self::hint_color(index),
+                            );
+                            if ui.checkbox(&mut shown, &category.name).changed() {
+                                if shown {
+                                    self.hint_categories_shown.insert(category.id);
+                                } else {
+                                    self.hint_categories_shown.remove(&category.id);
+                                }
+                            }
+                        });
+                    });
+            },
+        );
+    }
+
+    // An icon per shown hint, side by side when a map has several, named when hovered
+    pub(crate) fn hints_ui(&self, ui: &Ui, fullmap_position: Pos2, pointer_pos: Option<Pos2>) {
+        let painter = ui.painter();
+        let clip_rect = ui.clip_rect();
+        let origin = self.map_cell_origin(fullmap_position);
+        let cell_size = self.map_cell_size();
+        let radius = (cell_size.min_elem() / 4f32).clamp(3f32, 9f32);
+
+        let categories = self
+            .hint_categories
+            .iter()
+            .enumerate()
+            .filter(|(_, category)| self.hint_categories_shown.contains(&category.id))
+            .map(|(index, category)| (category.id, (index, category)))
+            .collect::<HashMap<_, _>>();
+
+        let mut hovered = None;
+        self.hints.iter().for_each(|((x, y), hints)| {
+            let hints = hints
+                .iter()
+                .filter_map(|hint| categories.get(&hint.category_id).map(|c| (hint, *c)))
+                .collect_vec();
+            if hints.is_empty() {
+                return;
+            }
+
+            let index = Vec2::new(
+                (x - self.map_min_max.x_min) as f32 + 0.5,
+                (y - self.map_min_max.y_min) as f32 + 0.5,
+            );
+            let center = origin + index * cell_size;
+            if !clip_rect
+                .expand(radius * hints.len() as f32)
+                .contains(center)
+            {
+                return;
+            }
+
+            let first_x = center.x - radius * (hints.len() - 1) as f32;
+            hints
+                .into_iter()
+                .enumerate()
+                .for_each(|(position, (hint, (index, category)))| {
+                    let icon = Pos2::new(first_x + 2f32 * radius * position as f32, center.y);
+                    painter.circle(
+                        icon,
+                        radius,
+                        Self::hint_color(index),
+                        Stroke::new(1f32, Color32::BLACK),
+                    );
+                    if radius >= 6f32 {
+                        if let Some(initial) = category.name.chars().next() {
+                            painter.text(
+                                icon,
+                                Align2::CENTER_CENTER,
+                                initial,
+                                FontId::proportional(radius * 1.4),
+                                Color32::WHITE,
+                            );
+                        }
+                    }
+
+                    if pointer_pos.is_some_and(|pos| pos.distance(icon) <= radius) {
+                        hovered = Some((icon, hint, category));
+                    }
+                });
+        });
+
+        if let Some((icon, hint, category)) = hovered {
+            let text = if hint.name.is_empty() {
+                category.name.clone()
+            } else {
+                format!("{} ({})", hint.name, category.name)
+            };
+            let galley = painter.layout_no_wrap(text, FontId::proportional(12f32), Color32::WHITE);
+            let rect = Align2::CENTER_BOTTOM
+                .anchor_size(icon - Vec2::new(0f32, radius + 4f32), galley.size())
+                .expand(2f32);
+            painter.rect_filled(rect, Rounding::same(3f32), Color32::from_black_alpha(200));
+            painter.galley(rect.min + Vec2::splat(2f32), galley, Color32::WHITE);
+        }
+    }
+
+    fn hint_color(category_index: usize) -> Color32 {
+        Self::HINT_COLORS[category_index % Self::HINT_COLORS.len()]
+    }
+}
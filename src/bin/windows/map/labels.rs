@@ -0,0 +1,56 @@
+use egui::{Align2, Color32, FontId, Pos2, Rounding, Ui, Vec2};
+use itertools::Itertools;
+
+use crate::{services::map_labels::place_labels, windows::main_window::MainWindow};
+
+impl MainWindow {
+    // Sub-areas are named from this zoom, areas below
+    const SUB_AREA_LABELS_ZOOM: f32 = 0.6;
+
+    // Area names when zoomed out and sub-area names when zoomed in, the names of the biggest zones
+    // are kept when they overlap
+    pub(crate) fn map_labels_ui(&self, ui: &Ui, fullmap_position: Pos2) {
+        let (labels, font_size) = if self.scale < Self::SUB_AREA_LABELS_ZOOM {
+            (&self.area_labels, 14f32)
+        } else {
+            (&self.sub_area_labels, 11f32)
+        };
+
+        let painter = ui.painter();
+        let clip_rect = ui.clip_rect();
+        let origin = self.map_cell_origin(fullmap_position);
+        let cell_size = self.map_cell_size();
+
+        let galleys = labels
+            .iter()
+            .filter_map(|label| {
+                let (x, y) = label.position;
+                let index = Vec2::new(
+                    x - self.map_min_max.x_min as f32 + 0.5,
+                    y - self.map_min_max.y_min as f32 + 0.5,
+                );
+                let center = origin + index * cell_size;
+                if !clip_rect.contains(center) {
+                    return None;
+                }
+
+                let galley = painter.layout_no_wrap(
+                    label.text.clone(),
+                    FontId::proportional(font_size),
+                    Color32::WHITE,
+                );
+                let rect = Align2::CENTER_CENTER
+                    .anchor_size(center, galley.size())
+                    .expand(2f32);
+                Some((rect, galley))
+            })
+            .collect_vec();
+
+        let rects = galleys.iter().map(|(rect, _)| *rect).collect_vec();
+        place_labels(&rects).into_iter().for_each(|index| {
+            let (rect, galley) = &galleys[index];
+            painter.rect_filled(*rect, Rounding::same(3f32), Color32::from_black_alpha(140));
+            painter.galley(rect.min + Vec2::splat(2f32), galley.clone(), Color32::WHITE);
+        });
+    }
+}
@@ -0,0 +1,91 @@
+use egui::{Color32, Pos2, Rect, Rounding, Sense, Stroke, Ui, Vec2};
+
+use crate::windows::main_window::{MainWindow, MissingDrops};
+
+impl MainWindow {
+    // Same ratio as the full map
+    const MINIMAP_SIZE: Vec2 = Vec2::new(200f32, 160f32);
+
+    pub(crate) fn minimap_rect(&self) -> Rect {
+        Rect::from_min_size(
+            self.map_viewport.right_bottom() - Self::MINIMAP_SIZE - Vec2::splat(12f32),
+            Self::MINIMAP_SIZE,
+        )
+    }
+
+    // The whole map from the smallest tiles, with the farm sub-areas and the part on screen.
+    // Clicking or dragging on it centers the view there
+    pub(crate) fn minimap_ui(
+        &mut self,
+        ui: &Ui,
+        fullmap_position: Pos2,
+        missing_drops: &MissingDrops,
+    ) {
+        let rect = self.minimap_rect();
+        let minimap_scale = rect.width() / Self::FULL_IMAGE_SIZE.x;
+        let painter = ui.painter().with_clip_rect(rect);
+
+        ui.painter().rect_filled(
+            rect.expand(2f32),
+            Rounding::same(3f32),
+            Color32::from_black_alpha(200),
+        );
+
+        let zoom_index = 0;
+        let tile_scale = minimap_scale / Self::ZOOMS[zoom_index];
+        let (columns, rows) = Self::image_number_from_zoom(zoom_index);
+        (0..columns as u16 * rows as u16).for_each(|index| {
+            let key = (index, zoom_index);
+            if let Some(image) = self.maps_images.get(&key) {
+                let tile_pos = Vec2::new(
+                    (index % columns as u16) as f32 * Self::IMAGE_SIZE.x,
+                    (index / columns as u16) as f32 * Self::IMAGE_SIZE.y,
+                );
+                painter.image(
+                    image.handle.id(),
+                    Rect::from_min_size(
+                        rect.min + tile_pos * tile_scale,
+                        image.handle.size_vec2() * tile_scale,
+                    ),
+                    Rect::from_min_max(Pos2::ZERO, Pos2::new(1f32, 1f32)),
+                    Color32::WHITE,
+                );
+            } else if !self.maps_images.is_requested(&key) {
+                self.maps_images.set_loading(key);
+                self.load_map_image(ui.ctx().clone(), index, zoom_index);
+            }
+        });
+
+        let cell_size = self.map_cell_size() / self.scale * minimap_scale;
+        let origin = rect.min + Self::MAPS_RECT.min.to_vec2() * minimap_scale;
+        self.sub_areas
+            .iter()
+            .filter(|(sub_area, _)| missing_drops.items_by_sub_area.contains_key(sub_area))
+            .flat_map(|(_, maps)| maps.iter())
+            .for_each(|map| {
+                let index = Vec2::new(
+                    (map.x - self.map_min_max.x_min) as f32,
+                    (map.y - self.map_min_max.y_min) as f32,
+                );
+                painter.rect_filled(
+                    Rect::from_min_size(origin + index * cell_size, cell_size),
+                    Rounding::ZERO,
+                    Color32::from_rgba_unmultiplied(60, 180, 255, 160),
+                );
+            });
+
+        let view = Rect::from_min_max(
+            rect.min + (self.map_viewport.min - fullmap_position) / self.scale * minimap_scale,
+            rect.min + (self.map_viewport.max - fullmap_position) / self.scale * minimap_scale,
+        );
+        painter.rect_stroke(view, Rounding::ZERO, Stroke::new(1.5, Color32::WHITE));
+
+        let response = ui.interact(rect, ui.id().with("minimap"), Sense::click_and_drag());
+        if response.clicked() || response.dragged() {
+            if let Some(pointer_pos) = response.interact_pointer_pos() {
+                let pos_on_map = (pointer_pos - rect.min) / minimap_scale;
+                self.map_position = self.map_viewport.center() - pos_on_map * self.scale;
+            }
+        }
+    }
+}
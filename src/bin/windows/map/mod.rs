@@ -0,0 +1,10 @@
+pub mod favorites;
+pub mod go_to;
+pub mod grid;
+pub mod heatmap;
+pub mod hints;
+pub mod labels;
+pub mod minimap;
+pub mod selection;
+pub mod tiles;
+pub mod zoom;
@@ -0,0 +1,112 @@
+use std::collections::HashSet;
+
+use egui::{Color32, Pos2, Rect, Rounding, Stroke, Ui};
+use itertools::Itertools;
+
+use crate::{database::models::sub_area::SubArea, windows::main_window::MainWindow};
+
+impl MainWindow {
+    // Shorter ctrl+drags are ctrl+clicks
+    const SELECTION_MIN_DRAG: f32 = 5f32;
+
+    // Sub-areas with a map centered in a part of the full map at zoom 1
+    fn sub_areas_in_view(&self, view: Rect) -> HashSet<&SubArea> {
+        self.sub_areas
+            .iter()
+            .filter(|(_, maps)| self.maps_in_view(maps, view))
+            .map(|(sub_area, _)| sub_area)
+            .collect()
+    }
+
+    pub(crate) fn sub_area_at(&self, x: i16, y: i16) -> Option<&SubArea> {
+        self.sub_areas
+            .iter()
+            .find(|(_, maps)| maps.iter().any(|map| map.x == x && map.y == y))
+            .map(|(sub_area, _)| sub_area)
+    }
+
+    // A ctrl+click toggles the sub-area under it, a ctrl+drag adds the ones in its rectangle
+    pub(crate) fn select_sub_areas(&mut self, selection: Rect, fullmap_position: Pos2) {
+        if selection.size().length() < Self::SELECTION_MIN_DRAG {
+            let (x_index, y_index) =
+                self.map_index_at((selection.max - fullmap_position).to_pos2());
+            let sub_area = self
+                .sub_area_at(
+                    x_index as i16 + self.map_min_max.x_min,
+                    y_index as i16 + self.map_min_max.y_min,
+                )
+                .cloned();
+
+            if let Some(sub_area) = sub_area {
+                if !self.selected_sub_areas.remove(&sub_area) {
+                    self.selected_sub_areas.insert(sub_area);
+                }
+            }
+            return;
+        }
+
+        let view = Rect::from_min_max(
+            ((selection.min - fullmap_position) / self.scale).to_pos2(),
+            ((selection.max - fullmap_position) / self.scale).to_pos2(),
+        );
+        let sub_areas = self
+            .sub_areas_in_view(view)
+            .into_iter()
+            .cloned()
+            .collect_vec();
+        self.selected_sub_areas.extend(sub_areas);
+    }
+
+    // Number of selected sub-areas, named when hovered, and a button to clear them
+    pub(crate) fn selection_label_ui(&mut self, ui: &mut Ui) {
+        let selection_label = if self.selected_sub_areas.is_empty() {
+            ui.label("No zone selected")
+        } else {
+            ui.label(format!("{} zones selected", self.selected_sub_areas.len()))
+        };
+        selection_label.on_hover_text(
+            self.selected_sub_areas
+                .iter()
+                .map(|sub_area| sub_area.name.as_str())
+                .sorted()
+                .chain(["Ctrl+click or ctrl+drag on the map to select zones, \
+                     a double click replaces them with its zone"])
+                .join("\n"),
+        );
+        if !self.selected_sub_areas.is_empty() && ui.small_button("x").clicked() {
+            self.selected_sub_areas.clear();
+        }
+    }
+
+    // Maps of the selected sub-areas, and the rectangle of the ctrl+drag going on
+    pub(crate) fn selection_ui(&self, ui: &Ui, fullmap_position: Pos2) {
+        self.selected_sub_areas
+            .iter()
+            .filter_map(|sub_area| self.sub_areas.get(sub_area))
+            .flatten()
+            .for_each(|map| {
+                self.map_rect_on_pos(
+                    ui,
+                    map.x as _,
+                    map.y as _,
+                    fullmap_position,
+                    Some(Color32::from_rgba_unmultiplied(255, 150, 40, 70)),
+                );
+            });
+
+        if let Some((start, end)) = self.selection_start.zip(ui.ctx().pointer_latest_pos()) {
+            let rect = Rect::from_two_pos(start, end);
+            let painter = ui.painter();
+            painter.rect_filled(
+                rect,
+                Rounding::ZERO,
+                Color32::from_rgba_unmultiplied(255, 150, 40, 30),
+            );
+            painter.rect_stroke(
+                rect,
+                Rounding::ZERO,
+                Stroke::new(1f32, Color32::from_rgb(255, 150, 40)),
+            );
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::VecDeque;
+
+use egui::{Color32, Context, Pos2, Rect, Ui, Vec2};
+use itertools::Itertools;
+use tracing::{trace_span, warn};
+
+use crate::windows::{image::Image, main_window::MainWindow};
+
+impl MainWindow {
+    // Prefetching waits while this many tiles are loading
+    const MAX_TILES_LOADING: usize = 4;
+
+    // Tiles of the current level scaled to the current scale, with the previous level under the
+    // ones still loading. Returns how many are loading
+    pub(crate) fn draw_map_tiles(&mut self, ui: &Ui, fullmap_position: Pos2, view: Rect) -> usize {
+        let zoom_index = self.zoom_index;
+        let tile_scale = self.scale / Self::ZOOMS[zoom_index];
+        let columns = Self::image_number_from_zoom(zoom_index).0 as u16;
+        let level_view = Rect::from_min_max(
+            view.min * Self::ZOOMS[zoom_index],
+            view.max * Self::ZOOMS[zoom_index],
+        );
+
+        let mut loading = 0;
+        Self::tiles_in_view(zoom_index, level_view, 0)
+            .into_iter()
+            .for_each(|index| {
+                let key = (index, zoom_index);
+                let tile_pos = Vec2::new(
+                    (index % columns) as f32 * Self::IMAGE_SIZE.x,
+                    (index / columns) as f32 * Self::IMAGE_SIZE.y,
+                );
+                let pos = fullmap_position + tile_pos * tile_scale;
+
+                if let Some(image) = self.maps_images.get(&key) {
+                    ui.painter().image(
+                        image.handle.id(),
+                        Rect::from_min_size(pos, image.handle.size_vec2() * tile_scale),
+                        Rect::from_min_max(Pos2::ZERO, Pos2::new(1f32, 1f32)),
+                        Color32::WHITE,
+                    );
+                } else {
+                    if !self.maps_images.is_requested(&key) {
+                        self.maps_images.set_loading(key);
+                        self.load_map_image(ui.ctx().clone(), index, zoom_index);
+                    }
+                    if self.maps_images.is_loading(&key) {
+                        loading += 1;
+                    }
+
+                    self.draw_previous_zoom_tiles(
+                        ui,
+                        Rect::from_min_size(pos, Self::IMAGE_SIZE * tile_scale),
+                        fullmap_position,
+                    );
+                }
+            });
+
+        loading
+    }
+
+    // Part of the previous zoom tiles under a tile of the current zoom still loading
+    fn draw_previous_zoom_tiles(&mut self, ui: &Ui, slot: Rect, fullmap_position: Pos2) {
+        let zoom_index = self.previous_zoom_index;
+        if zoom_index == self.zoom_index {
+            return;
+        }
+
+        let scale = self.scale / Self::ZOOMS[zoom_index];
+        let columns = Self::image_number_from_zoom(zoom_index).0 as u16;
+        let view = Rect::from_min_max(
+            ((slot.min - fullmap_position) / scale).to_pos2(),
+            ((slot.max - fullmap_position) / scale).to_pos2(),
+        );
+
+        Self::tiles_in_view(zoom_index, view, 0)
+            .into_iter()
+            .for_each(|index| {
+                let Some(image) = self.maps_images.get(&(index, zoom_index)) else {
+                    return;
+                };
+
+                let tile_pos = Vec2::new(
+                    (index % columns) as f32 * Self::IMAGE_SIZE.x,
+                    (index / columns) as f32 * Self::IMAGE_SIZE.y,
+                );
+                let tile = Rect::from_min_size(
+                    fullmap_position + tile_pos * scale,
+                    image.handle.size_vec2() * scale,
+                );
+                let shown = tile.intersect(slot);
+                if !shown.is_positive() {
+                    return;
+                }
+
+                let uv = Rect::from_min_max(
+                    ((shown.min - tile.min) / tile.size()).to_pos2(),
+                    ((shown.max - tile.min) / tile.size()).to_pos2(),
+                );
+                ui.painter()
+                    .image(image.handle.id(), shown, uv, Color32::WHITE);
+            });
+    }
+
+    // Indices of the tiles of a zoom in view, view being in pixels of that zoom's full map, with
+    // margin more tiles around
+    fn tiles_in_view(zoom_index: usize, view: Rect, margin: i32) -> Vec<u16> {
+        let (columns, rows) = Self::image_number_from_zoom(zoom_index);
+        let range = |min: f32, max: f32, size: f32, count: u8| {
+            let first = (min / size).floor() as i32 - margin;
+            let last = (max / size).ceil() as i32 - 1 + margin;
+            first.max(0)..=last.min(count as i32 - 1)
+        };
+
+        let columns_range = range(view.left(), view.right(), Self::IMAGE_SIZE.x, columns);
+        range(view.top(), view.bottom(), Self::IMAGE_SIZE.y, rows)
+            .flat_map(|row| {
+                columns_range
+                    .clone()
+                    .map(move |column| (row * columns as i32 + column) as u16)
+            })
+            .collect()
+    }
+
+    // Tiles around the view and the ones of the zooms next to the current one, queued behind the
+    // visible tiles: none starts while one of those is loading
+    pub(crate) fn prefetch_map_tiles(&mut self, ctx: &Context, view: Rect, visible_loading: usize) {
+        if visible_loading > 0 {
+            return;
+        }
+
+        let level_view = |zoom_index: usize| {
+            Rect::from_min_max(
+                view.min * Self::ZOOMS[zoom_index],
+                view.max * Self::ZOOMS[zoom_index],
+            )
+        };
+
+        let mut keys = Self::tiles_in_view(self.zoom_index, level_view(self.zoom_index), 1)
+            .into_iter()
+            .map(|index| (index, self.zoom_index))
+            .collect_vec();
+
+        [self.zoom_index.checked_sub(1), Some(self.zoom_index + 1)]
+            .into_iter()
+            .flatten()
+            .filter(|zoom_index| *zoom_index < Self::ZOOMS.len())
+            .for_each(|zoom_index| {
+                keys.extend(
+                    Self::tiles_in_view(zoom_index, level_view(zoom_index), 0)
+                        .into_iter()
+                        .map(|index| (index, zoom_index)),
+                );
+            });
+
+        let mut prefetch_queue = keys
+            .into_iter()
+            .filter(|key| !self.maps_images.is_requested(key))
+            .collect::<VecDeque<_>>();
+
+        while self.maps_images.loading_count() < Self::MAX_TILES_LOADING {
+            let Some((index, zoom_index)) = prefetch_queue.pop_front() else {
+                break;
+            };
+
+            self.maps_images.set_loading((index, zoom_index));
+            self.load_map_image(ctx.clone(), index, zoom_index);
+        }
+    }
+
+    pub(crate) fn load_map_image(&mut self, ctx: Context, index: u16, zoom_index: usize) {
+        let span = trace_span!("load_map_image");
+        let _guard = span.enter();
+
+        let tx = self.map_tx.clone();
+        let zoom = Self::ZOOMS[zoom_index];
+        // Decoding takes a while, it has its own thread so visible tiles don't wait behind others
+        tokio::task::spawn_blocking(move || {
+            let image = Image::map_from_ui_and_index(&ctx, index, zoom);
+            tx.send((image, index, zoom_index)).unwrap();
+            ctx.request_repaint();
+        });
+    }
+
+    pub(crate) fn check_for_new_map_images(&mut self) {
+        let span = trace_span!("check_for_new_images");
+        let _guard = span.enter();
+
+        self.map_rx
+            .try_iter()
+            .for_each(|(image, index, zoom_index)| match image {
+                Ok(image) => {
+                    let bytes = image.byte_size();
+                    self.maps_images.insert((index, zoom_index), image, bytes);
+                }
+                Err(error) => {
+                    warn!("Map tile {index} of zoom {zoom_index} not loaded: {error}");
+                    self.maps_images.set_failed((index, zoom_index));
+                }
+            });
+    }
+
+    pub(crate) fn image_number_from_zoom(zoom_index: usize) -> (u8, u8) {
+        let zoom = Self::ZOOMS[zoom_index];
+        (
+            ((Self::FULL_IMAGE_SIZE.x * zoom) / Self::IMAGE_SIZE.x).ceil() as u8,
+            ((Self::FULL_IMAGE_SIZE.y * zoom) / Self::IMAGE_SIZE.y).ceil() as u8,
+        )
+    }
+}
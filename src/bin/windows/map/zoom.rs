@@ -0,0 +1,57 @@
+use egui::{Context, Pos2};
+
+use crate::windows::main_window::MainWindow;
+
+impl MainWindow {
+    // Moves the scale a part of the way to the target each frame, in log space so zooming in and
+    // out feel the same
+    pub(crate) fn animate_zoom(&mut self, ctx: &Context) {
+        if self.scale == self.target_scale {
+            return;
+        }
+
+        let t = (ctx.input(|input_state| input_state.stable_dt) * 12f32).min(1f32);
+        let log_scale = self.scale.ln() + (self.target_scale.ln() - self.scale.ln()) * t;
+        let scale = if (log_scale - self.target_scale.ln()).abs() < 0.001 {
+            self.target_scale
+        } else {
+            log_scale.exp()
+        };
+
+        self.set_scale(scale, self.zoom_anchor);
+        ctx.request_repaint();
+    }
+
+    // The point of the map under anchor stays there
+    pub(crate) fn set_scale(&mut self, scale: f32, anchor: Pos2) {
+        self.map_position = anchor + (self.map_position - anchor) * (scale / self.scale);
+        self.scale = scale;
+
+        let zoom_index = Self::zoom_index_from_scale(scale);
+        if zoom_index != self.zoom_index {
+            self.previous_zoom_index = self.zoom_index;
+            self.zoom_index = zoom_index;
+        }
+    }
+
+    // Tiles are only shrunk, unless the scale is above the biggest zoom
+    fn zoom_index_from_scale(scale: f32) -> usize {
+        Self::ZOOMS
+            .iter()
+            .position(|zoom| *zoom >= scale - 0.001)
+            .unwrap_or(Self::ZOOMS.len() - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::windows::main_window::MainWindow;
+
+    #[test]
+    fn tiles_level_is_the_smallest_not_below_the_scale() {
+        assert_eq!(MainWindow::zoom_index_from_scale(0.2), 0);
+        assert_eq!(MainWindow::zoom_index_from_scale(0.25), 1);
+        assert_eq!(MainWindow::zoom_index_from_scale(0.6), 2);
+        assert_eq!(MainWindow::zoom_index_from_scale(1.7), 4);
+    }
+}
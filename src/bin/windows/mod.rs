@@ -1,4 +1,5 @@
 pub mod items_window;
 pub mod main_window;
+pub mod map;
 pub mod tabs;
 pub mod image;
\ No newline at end of file
@@ -0,0 +1,275 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    sync::mpsc::Sender,
+};
+
+use chrono::Local;
+use egui::{Grid, ScrollArea, Ui};
+use itertools::Itertools;
+
+use crate::{
+    crafting::planner::CraftPlan,
+    database::models::{
+        farming_session::FarmingSession,
+        item::{Item, ItemList, Quantity},
+        monster::Monster,
+        sub_area::SubArea,
+    },
+    services::drop_rates::{format_duration, DropRates},
+    windows::main_window::{AsyncStatus, ItemsRelations},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FarmingAction {
+    // In the zone selected on the map, if any
+    Start(Option<SubArea>),
+    Kill(Monster, i32),
+    Stop,
+}
+
+// Session running, the loot is added with the inventory
+pub struct ActiveSession {
+    pub session: FarmingSession,
+    pub sub_area: Option<SubArea>,
+    pub kills: BTreeMap<Monster, i32>,
+    pub loot: ItemList,
+}
+
+pub struct FarmingTab {
+    farming_action_tx: Sender<FarmingAction>,
+    new_ingredient_tx: Sender<(Item, Quantity)>,
+}
+
+impl FarmingTab {
+    pub fn new(
+        farming_action_tx: Sender<FarmingAction>,
+        new_ingredient_tx: Sender<(Item, Quantity)>,
+    ) -> Self {
+        Self {
+            farming_action_tx,
+            new_ingredient_tx,
+        }
+    }
+
+    pub fn show(
+        &mut self,
+        ui: &mut Ui,
+        items: &ItemsRelations,
+        craft_plan: &CraftPlan,
+        current_sub_areas: &HashSet<SubArea>,
+        active_session: &Option<ActiveSession>,
+        drop_rates: &DropRates,
+    ) {
+        // Monsters dropping each ingredient of the wish list, with their sub-areas
+        let mut droppers: HashMap<&Item, BTreeMap<&Monster, Vec<&SubArea>>> = HashMap::new();
+        items.iter().for_each(|(_, (_, ingredients))| {
+            if let AsyncStatus::Ready((ingredients, _steps)) = ingredients {
+                ingredients.iter().for_each(|(ingredient, (_, monsters))| {
+                    let item_droppers = droppers.entry(ingredient).or_default();
+                    monsters.iter().for_each(|(monster, sub_areas)| {
+                        item_droppers
+                            .entry(monster)
+                            .or_default()
+                            .extend(sub_areas.iter());
+                    });
+                });
+            }
+        });
+
+        ScrollArea::vertical().show(ui, |ui| {
+            match active_session {
+                Some(active_session) => self.session_ui(ui, active_session, craft_plan, &droppers),
+                None => {
+                    ui.horizontal(|ui| {
+                        // A session farms one zone, or none when several are selected
+                        let sub_area = current_sub_areas.iter().exactly_one().ok();
+                        let zone = match sub_area {
+                            Some(sub_area) => sub_area.name.clone(),
+                            None if current_sub_areas.is_empty() => {
+                                "no zone, select one on the map".to_owned()
+                            }
+                            None => format!("{} zones", current_sub_areas.len()),
+                        };
+                        ui.label(format!("Zone: {zone}"));
+
+                        if ui.button("Start session").clicked() {
+                            self.farming_action_tx
+                                .send(FarmingAction::Start(sub_area.cloned()))
+                                .unwrap();
+                        }
+                    });
+                }
+            }
+
+            ui.separator();
+            ui.heading("Remaining farm");
+            Self::estimates_ui(ui, craft_plan, &droppers, drop_rates);
+        });
+    }
+
+    fn session_ui(
+        &mut self,
+        ui: &mut Ui,
+        active_session: &ActiveSession,
+        craft_plan: &CraftPlan,
+        droppers: &HashMap<&Item, BTreeMap<&Monster, Vec<&SubArea>>>,
+    ) {
+        ui.horizontal(|ui| {
+            let zone = active_session
+                .sub_area
+                .as_ref()
+                .map(|sub_area| sub_area.name.as_str())
+                .unwrap_or("no zone");
+            let seconds =
+                (Local::now().naive_local() - active_session.session.started_at).num_seconds();
+            ui.label(format!(
+                "Farming in {zone} for {}",
+                format_duration(seconds as f32)
+            ));
+
+            if ui.button("Stop session").clicked() {
+                self.farming_action_tx.send(FarmingAction::Stop).unwrap();
+            }
+        });
+
+        // Monsters of the zone dropping something missing, and the ones already killed
+        let in_zone = |sub_areas: &Vec<&SubArea>| {
+            active_session
+                .sub_area
+                .as_ref()
+                .is_none_or(|sub_area| sub_areas.contains(&sub_area))
+        };
+        let monsters = craft_plan
+            .missing
+            .keys()
+            .filter_map(|item| droppers.get(item))
+            .flatten()
+            .filter(|(_, sub_areas)| in_zone(sub_areas))
+            .map(|(monster, _)| *monster)
+            .chain(active_session.kills.keys())
+            .collect::<BTreeSet<_>>();
+
+        ui.label("Kills");
+        Grid::new("session kills")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                monsters.into_iter().for_each(|monster| {
+                    ui.label(&monster.name);
+                    ui.horizontal(|ui| {
+                        let kills = *active_session.kills.get(monster).unwrap_or(&0);
+                        if ui.add_enabled(kills > 0, egui::Button::new("-")).clicked() {
+                            self.farming_action_tx
+                                .send(FarmingAction::Kill(monster.clone(), -1))
+                                .unwrap();
+                        }
+                        ui.label(kills.to_string());
+                        if ui.button("+").clicked() {
+                            self.farming_action_tx
+                                .send(FarmingAction::Kill(monster.clone(), 1))
+                                .unwrap();
+                        }
+                    });
+                    ui.end_row();
+                });
+            });
+
+        // Added to the inventory like in the resources tab
+        let loot_items = craft_plan
+            .missing
+            .keys()
+            .chain(active_session.loot.keys())
+            .collect::<BTreeSet<_>>();
+
+        ui.label("Loot");
+        Grid::new("session loot")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                loot_items.into_iter().for_each(|item| {
+                    ui.label(&item.name);
+                    ui.horizontal(|ui| {
+                        ui.label(active_session.loot.get(item).unwrap_or(&0).to_string());
+                        [1, 10].into_iter().for_each(|quantity| {
+                            if ui.button(format!("+{quantity}")).clicked() {
+                                self.new_ingredient_tx
+                                    .send((item.clone(), quantity))
+                                    .unwrap();
+                            }
+                        });
+                    });
+                    ui.end_row();
+                });
+            });
+    }
+
+    fn estimates_ui(
+        ui: &mut Ui,
+        craft_plan: &CraftPlan,
+        droppers: &HashMap<&Item, BTreeMap<&Monster, Vec<&SubArea>>>,
+        drop_rates: &DropRates,
+    ) {
+        let mut total_seconds = 0f32;
+        let mut unknown = 0;
+
+        Grid::new("farm estimates")
+            .num_columns(5)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Item");
+                ui.label("Missing");
+                ui.label("Best monster");
+                ui.label("Kills");
+                ui.label("Time");
+                ui.end_row();
+
+                craft_plan.missing.iter().for_each(|(item, quantity)| {
+                    let monsters = droppers
+                        .get(item)
+                        .into_iter()
+                        .flat_map(|monsters| monsters.keys().copied());
+
+                    ui.label(&item.name);
+                    ui.label(quantity.to_string());
+
+                    match drop_rates.farm_estimate(item, *quantity, monsters) {
+                        Some(estimate) => {
+                            ui.label(format!(
+                                "{} ({:.1}%)",
+                                estimate.monster.name,
+                                estimate.drop_rate * 100f32
+                            ));
+                            ui.label(format!("{:.0}", estimate.kills.ceil()));
+                            match estimate.seconds {
+                                Some(seconds) => {
+                                    total_seconds += seconds;
+                                    ui.label(format_duration(seconds));
+                                }
+                                None => {
+                                    unknown += 1;
+                                    ui.label("-");
+                                }
+                            }
+                        }
+                        None => {
+                            unknown += 1;
+                            ui.label("No observed drop");
+                            ui.label("-");
+                            ui.label("-");
+                        }
+                    }
+                    ui.end_row();
+                });
+            });
+
+        let unknown = if unknown > 0 {
+            format!(" ({unknown} items without estimate)")
+        } else {
+            String::new()
+        };
+        ui.label(format!(
+            "Estimated farm time: {}{unknown}",
+            format_duration(total_seconds)
+        ));
+    }
+}
@@ -0,0 +1,77 @@
+use std::sync::mpsc::Sender;
+
+use egui::{Color32, Grid, RichText, ScrollArea, Ui};
+
+use crate::database::models::{item::Item, journal_entry::JournalEntry};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryAction {
+    Undo,
+    Redo,
+}
+
+pub struct HistoryTab {
+    history_action_tx: Sender<HistoryAction>,
+}
+
+impl HistoryTab {
+    pub fn new(history_action_tx: Sender<HistoryAction>) -> Self {
+        Self { history_action_tx }
+    }
+
+    pub fn show(&mut self, ui: &mut Ui, history: &[(JournalEntry, Item)]) {
+        ui.horizontal(|ui| {
+            let can_undo = history.iter().any(|(entry, _)| !entry.undone);
+            let can_redo = history.iter().any(|(entry, _)| entry.undone);
+
+            if ui
+                .add_enabled(can_undo, egui::Button::new("Undo"))
+                .on_hover_text("Ctrl+Z")
+                .clicked()
+            {
+                self.history_action_tx.send(HistoryAction::Undo).unwrap();
+            }
+
+            if ui
+                .add_enabled(can_redo, egui::Button::new("Redo"))
+                .on_hover_text("Ctrl+Y")
+                .clicked()
+            {
+                self.history_action_tx.send(HistoryAction::Redo).unwrap();
+            }
+        });
+
+        ui.separator();
+
+        ScrollArea::vertical().show(ui, |ui| {
+            Grid::new("history")
+                .num_columns(5)
+                .striped(true)
+                .show(ui, |ui| {
+                    history.iter().for_each(|(entry, item)| {
+                        let color = if entry.undone {
+                            Color32::GRAY
+                        } else {
+                            ui.visuals().text_color()
+                        };
+
+                        ui.label(
+                            RichText::new(entry.created_at.format("%H:%M:%S").to_string())
+                                .color(color),
+                        );
+                        ui.label(RichText::new(&item.name).color(color));
+                        ui.label(RichText::new(format!("{:+}", entry.delta)).color(color));
+                        ui.label(RichText::new(&entry.target).color(color));
+
+                        let reason = if entry.undone {
+                            format!("{} (undone)", entry.reason)
+                        } else {
+                            entry.reason.clone()
+                        };
+                        ui.label(RichText::new(reason).color(color));
+                        ui.end_row();
+                    });
+                });
+        });
+    }
+}
@@ -1,47 +1,89 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use egui::Ui;
 use egui_dock::TabViewer;
 
 use crate::{
+    crafting::{
+        cost::{CraftCost, PriceList},
+        planner::CraftPlan,
+        recipe_graph::RecipeGraph,
+    },
     database::models::{
         item::{Item, ItemList},
+        journal_entry::JournalEntry,
         monster::Monster,
+        project::Project,
         sub_area::SubArea,
     },
+    services::drop_rates::DropRates,
     windows::{
         image::Image,
         main_window::{AsyncStatus, ItemsRelations},
     },
 };
 
-use super::item_tabs_data::ItemTabsData;
+use super::{farming_tab::ActiveSession, item_tabs_data::ItemTabsData};
 
 pub struct ItemTabsViewer<'a> {
     items: &'a ItemsRelations,
+    // Items kept by the project filter
+    visible_items: &'a ItemsRelations,
     ingredients_quantity: &'a ItemList,
     calculated_inventory: &'a ItemList,
+    craft_plan: &'a CraftPlan,
+    craft_cost: &'a CraftCost,
+    prices: &'a PriceList,
     items_images: &'a HashMap<Item, AsyncStatus<Image>>,
     monsters_images: &'a HashMap<Monster, AsyncStatus<Image>>,
-    current_sub_area: &'a Option<SubArea>,
+    current_sub_areas: &'a HashSet<SubArea>,
+    recipe_graph: &'a RecipeGraph,
+    history: &'a [(JournalEntry, Item)],
+    projects: &'a [Project],
+    items_project: &'a HashMap<Item, i32>,
+    projects_progress: &'a HashMap<i32, f32>,
+    active_session: &'a Option<ActiveSession>,
+    drop_rates: &'a DropRates,
 }
 
 impl<'a> ItemTabsViewer<'a> {
     pub fn new(
         items: &'a ItemsRelations,
+        visible_items: &'a ItemsRelations,
         ingredients_quantity: &'a ItemList,
         calculated_inventory: &'a ItemList,
+        craft_plan: &'a CraftPlan,
+        craft_cost: &'a CraftCost,
+        prices: &'a PriceList,
         items_images: &'a HashMap<Item, AsyncStatus<Image>>,
         monsters_images: &'a HashMap<Monster, AsyncStatus<Image>>,
-        current_sub_area: &'a Option<SubArea>,
+        current_sub_areas: &'a HashSet<SubArea>,
+        recipe_graph: &'a RecipeGraph,
+        history: &'a [(JournalEntry, Item)],
+        projects: &'a [Project],
+        items_project: &'a HashMap<Item, i32>,
+        projects_progress: &'a HashMap<i32, f32>,
+        active_session: &'a Option<ActiveSession>,
+        drop_rates: &'a DropRates,
     ) -> Self {
         Self {
             items,
+            visible_items,
             ingredients_quantity,
             calculated_inventory,
+            craft_plan,
+            craft_cost,
+            prices,
             items_images,
             monsters_images,
-            current_sub_area,
+            current_sub_areas,
+            recipe_graph,
+            history,
+            projects,
+            items_project,
+            projects_progress,
+            active_session,
+            drop_rates,
         }
     }
 }
@@ -52,23 +94,44 @@ impl<'a> TabViewer for ItemTabsViewer<'a> {
     fn ui(&mut self, ui: &mut Ui, tab: &mut Self::Tab) {
         match tab {
             ItemTabsData::SearchItem(tab) => tab.show(ui),
-            ItemTabsData::WishList(tab) => {
-                tab.show(ui, self.items, self.items_images, self.current_sub_area)
-            }
-            ItemTabsData::Resources(tab) => tab.show(
+            ItemTabsData::WishList(tab) => tab.show(
                 ui,
                 self.items,
                 self.items_images,
+                self.current_sub_areas,
+                self.craft_cost,
+                self.projects,
+                self.items_project,
+                self.projects_progress,
+            ),
+            ItemTabsData::Resources(tab) => tab.show(
+                ui,
+                self.visible_items,
+                self.items_images,
                 self.ingredients_quantity,
                 self.calculated_inventory,
-                self.current_sub_area,
+                self.craft_plan,
+                self.craft_cost,
+                self.current_sub_areas,
+                self.recipe_graph,
             ),
             ItemTabsData::Monsters(tab) => tab.show(
                 ui,
-                self.items,
+                self.visible_items,
                 self.monsters_images,
                 self.ingredients_quantity,
-                self.current_sub_area,
+                self.current_sub_areas,
+            ),
+            ItemTabsData::History(tab) => tab.show(ui, self.history),
+            ItemTabsData::Prices(tab) => tab.show(ui, self.items, self.craft_plan, self.prices),
+            ItemTabsData::Uses(tab) => tab.show(ui, self.ingredients_quantity, self.recipe_graph),
+            ItemTabsData::Farming(tab) => tab.show(
+                ui,
+                self.visible_items,
+                self.craft_plan,
+                self.current_sub_areas,
+                self.active_session,
+                self.drop_rates,
             ),
         }
     }
@@ -79,6 +142,10 @@ impl<'a> TabViewer for ItemTabsViewer<'a> {
             ItemTabsData::WishList(_) => "Wish list".into(),
             ItemTabsData::Resources(_) => "Resources".into(),
             ItemTabsData::Monsters(_) => "Monsters".into(),
+            ItemTabsData::History(_) => "History".into(),
+            ItemTabsData::Prices(_) => "Prices".into(),
+            ItemTabsData::Uses(_) => "Uses".into(),
+            ItemTabsData::Farming(_) => "Farming".into(),
         }
     }
 }
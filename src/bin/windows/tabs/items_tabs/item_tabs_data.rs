@@ -1,6 +1,7 @@
 use super::{
-    monsters_tab::MonstersTab, resources_tab::ResourcesTab, search_item_tabs::SearchItemTab,
-    wish_list_tab::WishListTab,
+    farming_tab::FarmingTab, history_tab::HistoryTab, monsters_tab::MonstersTab,
+    prices_tab::PricesTab, resources_tab::ResourcesTab, search_item_tabs::SearchItemTab,
+    uses_tab::UsesTab, wish_list_tab::WishListTab,
 };
 
 pub enum ItemTabsData {
@@ -8,4 +9,8 @@ pub enum ItemTabsData {
     WishList(WishListTab),
     Resources(ResourcesTab),
     Monsters(MonstersTab),
+    History(HistoryTab),
+    Prices(PricesTab),
+    Uses(UsesTab),
+    Farming(FarmingTab),
 }
@@ -1,6 +1,10 @@
+pub mod farming_tab;
+pub mod history_tab;
 pub mod item_tab_viewer;
 pub mod item_tabs_data;
 pub mod monsters_tab;
+pub mod prices_tab;
 pub mod resources_tab;
 pub mod search_item_tabs;
+pub mod uses_tab;
 pub mod wish_list_tab;
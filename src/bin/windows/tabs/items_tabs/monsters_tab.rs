@@ -1,10 +1,10 @@
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 use egui::{ImageButton, Ui};
 
 use crate::{
     database::models::{
-        item::{Item, ItemList},
+        item::{Item, ItemList, Quantity},
         monster::Monster,
         sub_area::SubArea,
     },
@@ -28,31 +28,30 @@ impl MonstersTab {
         items: &ItemsRelations,
         monsters_images: &HashMap<Monster, AsyncStatus<Image>>,
         ingredients_quantity: &ItemList,
-        current_sub_area: &Option<SubArea>,
+        current_sub_areas: &HashSet<SubArea>,
     ) {
-        let mut still_needed_ingredients_total: HashMap<&Item, (i16, i16)> = HashMap::new();
+        let mut still_needed_ingredients_total: HashMap<&Item, (Quantity, Quantity)> =
+            HashMap::new();
 
         items.iter().for_each(|(_, (quantity, ingredients))| {
             if let AsyncStatus::Ready((ingredients, _steps)) = ingredients {
                 ingredients
                     .iter()
                     .for_each(|(ingredient, (needed, monsters))| {
-                        let show_this = if let Some(sub_area) = current_sub_area {
-                            monsters
+                        let show_this = current_sub_areas.is_empty()
+                            || monsters
                                 .iter()
-                                .any(|(_, sub_areas)| sub_areas.contains(sub_area))
-                        } else {
-                            true
-                        };
+                                .any(|(_, sub_areas)| !sub_areas.is_disjoint(current_sub_areas));
                         if show_this {
                             still_needed_ingredients_total
                                 .entry(ingredient)
                                 .and_modify(|(needed_total, _)| {
-                                    *needed_total += needed * quantity;
+                                    *needed_total = needed_total
+                                        .saturating_add(needed.saturating_mul(*quantity));
                                 })
                                 .or_insert_with(|| {
                                     (
-                                        *needed * quantity,
+                                        needed.saturating_mul(*quantity),
                                         *ingredients_quantity.get(ingredient).unwrap_or(&0),
                                     )
                                 });
@@ -72,10 +71,10 @@ impl MonstersTab {
                         let monsters: BTreeSet<_> = monsters
                             .iter()
                             .filter_map(|(monster, sub_areas)| {
-                                if let Some(sub_area) = current_sub_area {
-                                    if !sub_areas.contains(sub_area) {
-                                        return None;
-                                    }
+                                if !current_sub_areas.is_empty()
+                                    && sub_areas.is_disjoint(current_sub_areas)
+                                {
+                                    return None;
                                 }
 
                                 Some(monster)
@@ -0,0 +1,174 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::mpsc::{self, Receiver, Sender},
+};
+
+use chrono::Local;
+use egui::{Grid, ScrollArea, TextEdit, Ui};
+use itertools::Itertools;
+use tracing::warn;
+
+use crate::{
+    crafting::{
+        cost::{format_kamas, PriceList, LOT_SIZES},
+        planner::CraftPlan,
+    },
+    database::models::{
+        item::Item,
+        price::{Kamas, Price},
+    },
+    services::{
+        database_worker::DatabaseWorker,
+        price_import::{import_prices, save_price},
+    },
+    windows::main_window::ItemsRelations,
+};
+
+pub struct PricesTab {
+    database_worker: DatabaseWorker,
+    // Reloaded prices for the main window
+    prices_tx: Sender<PriceList>,
+    import_path: String,
+    import_status: String,
+    import_status_tx: Sender<String>,
+    import_status_rx: Receiver<String>,
+    // Text typed in the price fields, by item id and lot size
+    edited_prices: HashMap<(i32, i16), String>,
+}
+
+impl PricesTab {
+    pub fn new(database_worker: DatabaseWorker, prices_tx: Sender<PriceList>) -> Self {
+        let import_path = Default::default();
+        let import_status = Default::default();
+        let (import_status_tx, import_status_rx) = mpsc::channel();
+        let edited_prices = HashMap::new();
+
+        Self {
+            database_worker,
+            prices_tx,
+            import_path,
+            import_status,
+            import_status_tx,
+            import_status_rx,
+            edited_prices,
+        }
+    }
+
+    pub fn show(
+        &mut self,
+        ui: &mut Ui,
+        items: &ItemsRelations,
+        craft_plan: &CraftPlan,
+        prices: &PriceList,
+    ) {
+        if let Some(import_status) = self.import_status_rx.try_iter().last() {
+            self.import_status = import_status;
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("CSV");
+            ui.add(
+                TextEdit::singleline(&mut self.import_path)
+                    .hint_text("item,lot_size,unit_price,date"),
+            );
+
+            if ui
+                .add_enabled(
+                    !self.import_path.trim().is_empty(),
+                    egui::Button::new("Import"),
+                )
+                .clicked()
+            {
+                self.import(ui, PathBuf::from(self.import_path.trim()));
+            }
+        });
+
+        if !self.import_status.is_empty() {
+            ui.label(&self.import_status);
+        }
+
+        ui.separator();
+
+        // Everything the wish list needs, the items in stock are left out
+        let priced_items = items
+            .keys()
+            .chain(craft_plan.tiers.iter().flat_map(|tier| tier.keys()))
+            .chain(craft_plan.missing.keys())
+            .unique()
+            .sorted()
+            .collect_vec();
+
+        ScrollArea::vertical().show(ui, |ui| {
+            Grid::new("prices")
+                .num_columns(LOT_SIZES.len() + 1)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("Item");
+                    LOT_SIZES.iter().for_each(|lot_size| {
+                        ui.label(format!("x{lot_size}"));
+                    });
+                    ui.end_row();
+
+                    priced_items.into_iter().for_each(|item| {
+                        ui.label(&item.name);
+                        LOT_SIZES.iter().for_each(|lot_size| {
+                            self.price_field_ui(ui, item, *lot_size, prices);
+                        });
+                        ui.end_row();
+                    });
+                });
+        });
+    }
+
+    // Saved when enter is pressed or the field loses focus
+    fn price_field_ui(&mut self, ui: &mut Ui, item: &Item, lot_size: i16, prices: &PriceList) {
+        let hint = prices
+            .unit_price(item, lot_size)
+            .map(format_kamas)
+            .unwrap_or_default();
+
+        let text = self.edited_prices.entry((item.id, lot_size)).or_default();
+        let response = ui.add(
+            TextEdit::singleline(text)
+                .hint_text(hint)
+                .desired_width(90f32),
+        );
+
+        if response.lost_focus() {
+            if let Ok(unit_price) = text.trim().parse::<Kamas>() {
+                let price = Price::new(item.id, lot_size, unit_price, Local::now().date_naive());
+                let prices_tx = self.prices_tx.clone();
+
+                self.database_worker
+                    .query(ui.ctx(), prices_tx, move |connection| {
+                        // Loaded again anyway, the price shown is then the saved one
+                        if let Err(error) = save_price(&price, connection) {
+                            warn!("Price not saved: {error}");
+                        }
+                        PriceList::load(connection)
+                    });
+            }
+
+            self.edited_prices.remove(&(item.id, lot_size));
+        }
+    }
+
+    fn import(&mut self, ui: &Ui, path: PathBuf) {
+        let prices_tx = self.prices_tx.clone();
+        let import_status_tx = self.import_status_tx.clone();
+        self.import_status = "Importing...".to_owned();
+
+        self.database_worker
+            .query(ui.ctx(), import_status_tx, move |connection| {
+                let (imported, errors) = import_prices(&path, connection);
+                prices_tx.send(PriceList::load(connection)).unwrap();
+
+                format!("{imported} prices imported")
+                    + &errors
+                        .iter()
+                        .map(|error| format!("\n{error}"))
+                        .collect::<String>()
+            });
+    }
+}
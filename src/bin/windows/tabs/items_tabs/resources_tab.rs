@@ -1,12 +1,19 @@
 use std::{
     cmp,
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, HashSet},
     sync::mpsc::Sender,
 };
 
 use crate::{
+    crafting::{
+        cost::{format_kamas, CraftCost},
+        craft_tree::CraftTreeNode,
+        planner::CraftPlan,
+        recipe_graph::RecipeGraph,
+    },
     database::models::{
-        item::{Item, ItemList},
+        item::{Item, ItemList, Quantity},
+        price::Kamas,
         sub_area::SubArea,
     },
     windows::{
@@ -16,11 +23,23 @@ use crate::{
     },
 };
 
-use egui::{ImageButton, Key, Layout, PointerButton, Ui, Vec2};
+use egui::{
+    CollapsingHeader, ImageButton, Key, Layout, PointerButton, RichText, ScrollArea, Ui, Vec2,
+};
 use egui_modal::Modal;
+use itertools::Itertools;
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum ResourcesView {
+    // One page per crafting step
+    Steps,
+    // Ingredients under the item they are used for
+    Tree,
+}
 
 pub struct ResourcesTab {
-    new_ingredient_tx: Sender<(Item, isize)>,
+    new_ingredient_tx: Sender<(Item, Quantity)>,
+    view: ResourcesView,
     modal_clicked_item: Option<Item>,
     modal_quantity: String,
     current_page: usize,
@@ -28,14 +47,16 @@ pub struct ResourcesTab {
 }
 
 impl ResourcesTab {
-    pub fn new(new_ingredient_tx: Sender<(Item, isize)>) -> Self {
+    pub fn new(new_ingredient_tx: Sender<(Item, Quantity)>) -> Self {
         let modal_clicked_item = Default::default();
         let modal_quantity = Default::default();
         let current_page = 1;
-        let max_page = 10; // todo
+        let max_page = 1;
+        let view = ResourcesView::Steps;
 
         Self {
             new_ingredient_tx,
+            view,
             modal_clicked_item,
             modal_quantity,
             current_page,
@@ -48,10 +69,33 @@ impl ResourcesTab {
         ui: &mut Ui,
         items: &ItemsRelations,
         items_images: &HashMap<Item, AsyncStatus<Image>>,
-        _ingredients_quantity: &ItemList,
+        ingredients_quantity: &ItemList,
         calculated_inventory: &ItemList,
-        current_sub_area: &Option<SubArea>,
+        craft_plan: &CraftPlan,
+        craft_cost: &CraftCost,
+        current_sub_areas: &HashSet<SubArea>,
+        recipe_graph: &RecipeGraph,
     ) {
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.view, ResourcesView::Steps, "Steps");
+            ui.selectable_value(&mut self.view, ResourcesView::Tree, "Tree");
+        });
+
+        if self.view == ResourcesView::Tree {
+            ItemsWindow::missing_cost_ui(ui, craft_cost);
+            Self::craft_plan_ui(ui, craft_plan, craft_cost);
+            Self::tree_ui(ui, items, ingredients_quantity, recipe_graph);
+            return;
+        }
+
+        // One page for the base ingredients and one per crafting step
+        self.max_page = items
+            .keys()
+            .map(|item| recipe_graph.depth(item))
+            .max()
+            .unwrap_or(1)
+            .max(1);
+
         if ui.input(|reader| reader.key_pressed(Key::ArrowLeft)) {
             self.current_page = self.current_page.checked_sub(1).unwrap_or(1);
         }
@@ -62,33 +106,28 @@ impl ResourcesTab {
         self.current_page = cmp::max(cmp::min(self.current_page, self.max_page), 1);
 
         // Same as ingredient_quantity but with quantity needed
-        let mut showed_ingredients = BTreeMap::new();
+        let mut showed_ingredients: BTreeMap<&Item, (Quantity, &Quantity)> = BTreeMap::new();
 
-        self.max_page = 1;
         items.iter().for_each(|(_, (quantity, ingredients))| {
             if let AsyncStatus::Ready((ingredients, steps)) = ingredients {
-                self.max_page = cmp::max(self.max_page, steps.len() + 1);
-
                 if self.current_page == 1 {
                     ingredients
                         .iter()
                         .for_each(|(ingredient, (needed, monsters))| {
-                            let show_this = if let Some(sub_area) = current_sub_area {
-                                monsters
-                                    .iter()
-                                    .any(|(_, sub_areas)| sub_areas.contains(sub_area))
-                            } else {
-                                true
-                            };
+                            let show_this = current_sub_areas.is_empty()
+                                || monsters.iter().any(|(_, sub_areas)| {
+                                    !sub_areas.is_disjoint(current_sub_areas)
+                                });
                             if show_this {
                                 showed_ingredients
                                     .entry(ingredient)
                                     .and_modify(|(needed_total, _)| {
-                                        *needed_total += needed * quantity;
+                                        *needed_total = needed_total
+                                            .saturating_add(needed.saturating_mul(*quantity));
                                     })
                                     .or_insert_with(|| {
                                         (
-                                            *needed * quantity,
+                                            needed.saturating_mul(*quantity),
                                             calculated_inventory.get(ingredient).unwrap_or(&0),
                                         )
                                     });
@@ -99,11 +138,12 @@ impl ResourcesTab {
                         showed_ingredients
                             .entry(item_step)
                             .and_modify(|(current_needed, _)| {
-                                *current_needed += needed * quantity;
+                                *current_needed =
+                                    current_needed.saturating_add(needed.saturating_mul(*quantity));
                             })
                             .or_insert_with(|| {
                                 (
-                                    *needed * quantity,
+                                    needed.saturating_mul(*quantity),
                                     calculated_inventory.get(item_step).unwrap_or(&0),
                                 )
                             });
@@ -121,7 +161,7 @@ impl ResourcesTab {
                     self.modal_clicked_item = None;
                     quantity_modal.close();
                 } else if ui.button("Add").clicked() {
-                    if let Ok(quantity) = self.modal_quantity.parse::<isize>() {
+                    if let Ok(quantity) = self.modal_quantity.parse::<Quantity>() {
                         let item = self.modal_clicked_item.take();
                         self.new_ingredient_tx
                             .send((item.unwrap(), quantity))
@@ -132,6 +172,9 @@ impl ResourcesTab {
             });
         });
 
+        ItemsWindow::missing_cost_ui(ui, craft_cost);
+        Self::craft_plan_ui(ui, craft_plan, craft_cost);
+
         ui.vertical_centered(|ui| ui.label(format!("{}/{}", self.current_page, self.max_page)));
         ui.horizontal_wrapped(|ui| {
             showed_ingredients
@@ -147,7 +190,15 @@ impl ResourcesTab {
                                         ItemsWindow::get_sized_texture(image),
                                     ));
 
-                                    let response = ui.add(button).on_hover_text(&item.name);
+                                    let used_in = recipe_graph
+                                        .used_in(item)
+                                        .into_iter()
+                                        .map(|(result, _)| format!("\n- {}", result.name))
+                                        .collect::<String>();
+
+                                    let response = ui
+                                        .add(button)
+                                        .on_hover_text(format!("{}{used_in}", item.name));
                                     if response.clicked_by(PointerButton::Primary) {
                                         self.new_ingredient_tx.send((item.clone(), 1)).unwrap();
                                     } else if response.clicked_by(PointerButton::Secondary) {
@@ -158,7 +209,7 @@ impl ResourcesTab {
                                         self.new_ingredient_tx
                                             .send((
                                                 item.clone(),
-                                                *needed as isize - in_inventory as isize,
+                                                needed.saturating_sub(in_inventory),
                                             ))
                                             .unwrap();
                                     }
@@ -171,4 +222,96 @@ impl ResourcesTab {
                 });
         });
     }
+
+    // Summary of the craft plan, the pages below still show every ingredient
+    fn craft_plan_ui(ui: &mut Ui, craft_plan: &CraftPlan, craft_cost: &CraftCost) {
+        fn item_list_label(ui: &mut Ui, title: &str, item_list: &ItemList) {
+            if !item_list.is_empty() {
+                let items = item_list
+                    .iter()
+                    .map(|(item, quantity)| format!("{quantity} {}", item.name))
+                    .join(", ");
+                ui.label(format!("{title}: {items}"));
+            }
+        }
+
+        CollapsingHeader::new("Craft plan")
+            .default_open(false)
+            .show(ui, |ui| {
+                craft_plan
+                    .tiers
+                    .iter()
+                    .enumerate()
+                    .for_each(|(index, tier)| {
+                        item_list_label(ui, &format!("Craft, step {}", index + 1), tier);
+                    });
+                item_list_label(
+                    ui,
+                    "Crafted items from inventory",
+                    &craft_plan.intermediates_from_stock,
+                );
+                item_list_label(ui, "Resources from inventory", &craft_plan.base_from_stock);
+                item_list_label(ui, "Missing", &craft_plan.missing);
+
+                craft_cost.craft_or_buy.iter().for_each(|craft_or_buy| {
+                    let advice = match craft_or_buy.craft_is_cheaper() {
+                        Some(true) => "craft",
+                        Some(false) => "buy",
+                        None => "missing prices",
+                    };
+                    let cost =
+                        |cost: Option<Kamas>| cost.map(format_kamas).unwrap_or("?".to_owned());
+
+                    ui.label(format!(
+                        "{} {}: {advice} (craft {}, buy {})",
+                        craft_or_buy.quantity,
+                        craft_or_buy.item.name,
+                        cost(craft_or_buy.craft_cost),
+                        cost(craft_or_buy.buy_cost),
+                    ));
+                });
+            });
+    }
+
+    fn tree_ui(
+        ui: &mut Ui,
+        items: &ItemsRelations,
+        ingredients_quantity: &ItemList,
+        recipe_graph: &RecipeGraph,
+    ) {
+        fn node_ui(ui: &mut Ui, node: &CraftTreeNode) {
+            let complete = node.is_complete();
+            let text = format!("{}/{} {}", node.from_stock, node.needed, node.item.name);
+            let text = if complete {
+                RichText::new(text)
+            } else {
+                RichText::new(text).color(ui.visuals().error_fg_color)
+            };
+
+            if node.children.is_empty() {
+                ui.label(text);
+            } else {
+                // Blocking branches are opened
+                CollapsingHeader::new(text)
+                    .id_salt(node.item.id)
+                    .default_open(!complete)
+                    .show(ui, |ui| {
+                        node.children.iter().for_each(|child| node_ui(ui, child));
+                    });
+            }
+        }
+
+        let wish_list = ItemList::with_items(
+            items
+                .iter()
+                .map(|(item, (quantity, _))| (item.clone(), *quantity))
+                .collect(),
+        );
+
+        ScrollArea::vertical().show(ui, |ui| {
+            CraftTreeNode::for_wish_list(recipe_graph, &wish_list, ingredients_quantity)
+                .iter()
+                .for_each(|tree| node_ui(ui, tree));
+        });
+    }
 }
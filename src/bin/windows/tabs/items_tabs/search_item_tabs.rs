@@ -1,6 +1,7 @@
 use std::sync::{
     self,
     mpsc::{Receiver, Sender},
+    Arc,
 };
 
 use diesel::{
@@ -11,10 +12,11 @@ use egui::{Context, ImageButton, TextEdit, Ui};
 use egui_modal::Modal;
 use itertools::Itertools;
 use tokio::task::JoinHandle;
-use tracing::trace_span;
+use tracing::{trace_span, warn};
 
 use crate::{
-    database::models::item::Item,
+    database::models::item::{Item, Quantity},
+    services::item_search::ItemSearch,
     windows::{image::Image, items_window::ItemsWindow, main_window::AsyncStatus},
 };
 
@@ -23,26 +25,30 @@ pub struct SearchItemTab {
     modal_quantity: String,
     modal_clicked_item: Option<Item>,
     items: Vec<(Item, AsyncStatus<Image>)>,
+    current_page: usize,
+    has_next_page: bool,
+    item_search: Arc<ItemSearch>,
     pool: Pool<ConnectionManager<PgConnection>>,
-    items_tx: Sender<(String, Vec<Item>)>,
-    items_rx: Receiver<(String, Vec<Item>)>,
+    items_tx: Sender<(String, usize, Vec<Item>, bool)>,
+    items_rx: Receiver<(String, usize, Vec<Item>, bool)>,
     item_image_tx: Sender<(usize, Image)>,
     item_image_rx: Receiver<(usize, Image)>,
-    item_clicked_tx: Sender<(Item, i16)>,
-    current_search_thread: Option<(String, JoinHandle<()>)>,
+    item_clicked_tx: Sender<(Item, Quantity)>,
+    current_search_thread: Option<(String, usize, JoinHandle<()>)>,
 }
 
 impl SearchItemTab {
-    const CATEGORY_SEARCHED: [i16; 3] = [0, 1, 2];
-
     pub fn new(
         pool: Pool<ConnectionManager<PgConnection>>,
-        item_clicked_tx: Sender<(Item, i16)>,
+        item_clicked_tx: Sender<(Item, Quantity)>,
     ) -> Self {
         let search_bar_text = Default::default();
         let modal_quantity = Default::default();
         let modal_clicked_item = None;
         let items = Default::default();
+        let current_page = 0;
+        let has_next_page = false;
+        let item_search = Arc::new(ItemSearch::new(&mut pool.get().unwrap()));
         let (items_tx, items_rx) = sync::mpsc::channel();
         let (item_image_tx, item_image_rx) = sync::mpsc::channel();
         let current_search_thread = None;
@@ -52,6 +58,9 @@ impl SearchItemTab {
             modal_quantity,
             modal_clicked_item,
             items,
+            current_page,
+            has_next_page,
+            item_search,
             pool,
             items_tx,
             items_rx,
@@ -66,9 +75,21 @@ impl SearchItemTab {
         let span = trace_span!("show items window");
         let _guard = span.enter();
 
-        if let Ok(items) = self.items_rx.try_recv() {
+        // Searches replaced by a newer one can still finish, their results are ignored
+        let current_search = self
+            .current_search_thread
+            .as_ref()
+            .map(|(text, page, _)| (text.clone(), *page));
+        let search_result = self
+            .items_rx
+            .try_iter()
+            .filter(|(text, page, _, _)| current_search == Some((text.clone(), *page)))
+            .last();
+
+        if let Some((_, page, items, has_next_page)) = search_result {
+            self.current_page = page;
+            self.has_next_page = has_next_page;
             self.items = items
-                .1
                 .into_iter()
                 .enumerate()
                 .map(|(index, item)| {
@@ -91,7 +112,7 @@ impl SearchItemTab {
                     self.modal_clicked_item = None;
                     quantity_modal.close();
                 } else if ui.button("Add").clicked() {
-                    if let Ok(quantity) = self.modal_quantity.parse::<i16>() {
+                    if let Ok(quantity) = self.modal_quantity.parse::<Quantity>() {
                         let item = self.modal_clicked_item.take();
                         self.item_clicked_tx
                             .send((item.unwrap(), quantity))
@@ -109,15 +130,33 @@ impl SearchItemTab {
                 .response;
 
             if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-                self.search_event(ui.ctx());
+                self.search_event(ui.ctx(), 0);
             }
 
             if ui.button("Search").clicked() {
-                self.search_event(ui.ctx());
+                self.search_event(ui.ctx(), 0);
             }
         });
 
-        ui.label(self.items.len().to_string());
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(self.current_page > 0, egui::Button::new("<"))
+                .clicked()
+            {
+                self.search_event(ui.ctx(), self.current_page - 1);
+            }
+
+            ui.label(format!("Page {}", self.current_page + 1));
+
+            if ui
+                .add_enabled(self.has_next_page, egui::Button::new(">"))
+                .clicked()
+            {
+                self.search_event(ui.ctx(), self.current_page + 1);
+            }
+
+            ui.label(self.items.len().to_string());
+        });
 
         ui.horizontal_wrapped(|ui| {
             self.items.iter().for_each(|item| {
@@ -146,48 +185,46 @@ impl SearchItemTab {
         let image_id = item.image_id;
 
         tokio::spawn(async move {
-            let image = Image::item_from_image_id(&ctx, image_id as _);
+            let image = match Image::item_from_image_id(&ctx, image_id as _) {
+                Ok(image) => image,
+                Err(error) => {
+                    warn!("Image {image_id} not loaded: {error}");
+                    return;
+                }
+            };
             tx.send((index, image)).unwrap();
             ctx.request_repaint();
         });
     }
 
-    fn search_event(&mut self, ctx: &Context) {
+    fn search_event(&mut self, ctx: &Context, page: usize) {
         if let Some(ref current_search) = self.current_search_thread {
-            if current_search.0 != self.search_bar_text {
-                current_search.1.abort();
-                self.search_items(ctx);
+            if current_search.0 != self.search_bar_text || current_search.1 != page {
+                current_search.2.abort();
+                self.search_items(ctx, page);
             }
         } else {
-            self.search_items(ctx);
+            self.search_items(ctx, page);
         }
     }
 
-    fn search_items(&mut self, ctx: &Context) {
-        use crate::database::functions::f_unaccent;
-        use crate::database::schema::{items, items::*};
-        use diesel::prelude::*;
-
+    fn search_items(&mut self, ctx: &Context, page: usize) {
         let tx = self.items_tx.clone();
         let pool = self.pool.clone();
+        let item_search = self.item_search.clone();
         let search_text = self.search_bar_text.clone();
         let ctx = ctx.clone();
 
         self.current_search_thread = Some((
             search_text.clone(),
-            tokio::spawn(async move {
+            page,
+            tokio::task::spawn_blocking(move || {
                 let mut connection = pool.get().unwrap();
 
-                let items = items::table
-                    .filter(f_unaccent(name).ilike(f_unaccent(format!("%{search_text}%"))))
-                    .filter(category.eq_any(Self::CATEGORY_SEARCHED))
-                    .filter(image_id.ne(89042))
-                    .limit(100)
-                    .offset(0)
-                    .load::<Item>(&mut connection)
-                    .unwrap();
+                let (items, has_next_page) =
+                    item_search.search(&search_text, page, &mut connection);
 
-                tx.send((search_text, items)).unwrap();
+                tx.send((search_text, page, items, has_next_page)).unwrap();
                 ctx.request_repaint();
             }),
         ));
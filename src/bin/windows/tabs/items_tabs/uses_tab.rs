@@ -0,0 +1,127 @@
+use egui::{Grid, ScrollArea, TextEdit, Ui};
+
+use crate::{
+    crafting::recipe_graph::RecipeGraph,
+    database::models::item::{Item, ItemList},
+};
+
+pub struct UsesTab {
+    search_text: String,
+    selected_item: Option<Item>,
+}
+
+impl UsesTab {
+    const MAX_MATCHES: usize = 20;
+
+    pub fn new() -> Self {
+        let search_text = Default::default();
+        let selected_item = None;
+
+        Self {
+            search_text,
+            selected_item,
+        }
+    }
+
+    pub fn show(
+        &mut self,
+        ui: &mut Ui,
+        ingredients_quantity: &ItemList,
+        recipe_graph: &RecipeGraph,
+    ) {
+        ui.add(TextEdit::singleline(&mut self.search_text).hint_text("Item used in recipes"));
+
+        let search_text = self.search_text.trim();
+        if !search_text.is_empty() {
+            recipe_graph
+                .find_items(search_text)
+                .into_iter()
+                .take(Self::MAX_MATCHES)
+                .for_each(|item| {
+                    if ui
+                        .selectable_label(self.selected_item.as_ref() == Some(item), &item.name)
+                        .clicked()
+                    {
+                        self.selected_item = Some(item.clone());
+                        self.search_text.clear();
+                    }
+                });
+        }
+
+        ui.separator();
+
+        ScrollArea::vertical().show(ui, |ui| {
+            if let Some(selected_item) = self.selected_item.clone() {
+                ui.heading(format!("{} is used in", selected_item.name));
+                self.used_in_ui(ui, &selected_item, ingredients_quantity, recipe_graph);
+                ui.separator();
+            }
+
+            ui.heading("Craftable now");
+            self.craftable_now_ui(ui, ingredients_quantity, recipe_graph);
+        });
+    }
+
+    fn used_in_ui(
+        &mut self,
+        ui: &mut Ui,
+        item: &Item,
+        ingredients_quantity: &ItemList,
+        recipe_graph: &RecipeGraph,
+    ) {
+        let used_in = recipe_graph.used_in(item);
+        if used_in.is_empty() {
+            ui.label("No recipe");
+            return;
+        }
+
+        Grid::new("used_in")
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Item");
+                ui.label("Per craft");
+                ui.label("Craftable");
+                ui.end_row();
+
+                used_in.into_iter().for_each(|(result, quantity)| {
+                    if ui.link(&result.name).clicked() {
+                        self.selected_item = Some(result.clone());
+                    }
+                    ui.label(quantity.to_string());
+                    ui.label(
+                        recipe_graph
+                            .max_craftable(result, ingredients_quantity)
+                            .to_string(),
+                    );
+                    ui.end_row();
+                });
+            });
+    }
+
+    fn craftable_now_ui(
+        &mut self,
+        ui: &mut Ui,
+        ingredients_quantity: &ItemList,
+        recipe_graph: &RecipeGraph,
+    ) {
+        let craftable_now = recipe_graph.craftable_now(ingredients_quantity);
+        if craftable_now.is_empty() {
+            ui.label("Nothing can be crafted with the inventory");
+            return;
+        }
+
+        Grid::new("craftable_now")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                craftable_now.into_iter().for_each(|(item, count)| {
+                    if ui.link(&item.name).clicked() {
+                        self.selected_item = Some(item.clone());
+                    }
+                    ui.label(format!("x{count}"));
+                    ui.end_row();
+                });
+            });
+    }
+}
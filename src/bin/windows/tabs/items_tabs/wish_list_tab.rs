@@ -1,10 +1,22 @@
-use std::{collections::HashMap, sync::mpsc::Sender};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::mpsc::Sender,
+};
 
-use egui::{ImageButton, Layout, PointerButton, Ui, Vec2};
+use chrono::{Local, NaiveDate};
+use egui::{
+    Color32, ComboBox, ImageButton, Layout, PointerButton, ProgressBar, RichText, TextEdit, Ui,
+    Vec2,
+};
 use egui_modal::Modal;
 
 use crate::{
-    database::models::{item::Item, sub_area::SubArea},
+    crafting::cost::CraftCost,
+    database::models::{
+        item::{Item, Quantity},
+        project::Project,
+        sub_area::SubArea,
+    },
     windows::{
         image::Image,
         items_window::ItemsWindow,
@@ -12,24 +24,49 @@ use crate::{
     },
 };
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProjectAction {
+    Create(String, Option<NaiveDate>),
+    Delete(i32),
+    // Done earlier or later, by swapping with the neighbour
+    Raise(i32),
+    Lower(i32),
+    // None puts the item back in the main list
+    MoveItem(Item, Option<i32>),
+}
+
 pub struct WishListTab {
-    remove_item_tx: Sender<(Item, i16, bool)>,
+    remove_item_tx: Sender<(Item, Quantity, bool)>,
+    project_action_tx: Sender<ProjectAction>,
     modal_quantity: String,
     modal_craft: bool,
     modal_clicked_item: Option<Item>,
+    modal_project_id: Option<i32>,
+    new_project_name: String,
+    new_project_deadline: String,
 }
 
 impl WishListTab {
-    pub fn new(remove_item_tx: Sender<(Item, i16, bool)>) -> Self {
+    pub fn new(
+        remove_item_tx: Sender<(Item, Quantity, bool)>,
+        project_action_tx: Sender<ProjectAction>,
+    ) -> Self {
         let modal_quantity = Default::default();
         let modal_craft = false;
         let modal_clicked_item = Default::default();
+        let modal_project_id = None;
+        let new_project_name = Default::default();
+        let new_project_deadline = Default::default();
 
         Self {
             remove_item_tx,
+            project_action_tx,
             modal_quantity,
             modal_craft,
             modal_clicked_item,
+            modal_project_id,
+            new_project_name,
+            new_project_deadline,
         }
     }
 
@@ -38,8 +75,15 @@ impl WishListTab {
         ui: &mut Ui,
         items: &ItemsRelations,
         items_images: &HashMap<Item, AsyncStatus<Image>>,
-        current_sub_area: &Option<SubArea>,
+        current_sub_areas: &HashSet<SubArea>,
+        craft_cost: &CraftCost,
+        projects: &[Project],
+        items_project: &HashMap<Item, i32>,
+        projects_progress: &HashMap<i32, f32>,
     ) {
+        ItemsWindow::missing_cost_ui(ui, craft_cost);
+        self.new_project_ui(ui, projects);
+
         let quantity_modal = Modal::new(ui.ctx(), "wish list modal");
 
         quantity_modal.show(|ui| {
@@ -50,7 +94,7 @@ impl WishListTab {
                     self.modal_clicked_item = None;
                     quantity_modal.close();
                 } else if ui.button("Add").clicked() {
-                    if let Ok(quantity) = self.modal_quantity.parse::<i16>() {
+                    if let Ok(quantity) = self.modal_quantity.parse::<Quantity>() {
                         let item = self.modal_clicked_item.take();
                         self.remove_item_tx
                             .send((item.unwrap(), quantity, self.modal_craft))
@@ -59,54 +103,217 @@ impl WishListTab {
                     }
                 }
             });
-        });
 
-        ui.horizontal_wrapped(|ui| {
-            items.iter().for_each(|(item, (quantity, ingredients))| {
-                if let Some(AsyncStatus::Ready(image)) = items_images.get(item) {
-                    let show_this = current_sub_area
-                        .clone()
-                        .map(|sub_area| {
-                            if let AsyncStatus::Ready((ingredients, _steps)) = ingredients {
-                                return ingredients.iter().any(|(_, (_, monsters))| {
-                                    monsters
-                                        .iter()
-                                        .any(|(_, sub_areas)| sub_areas.contains(&sub_area))
-                                });
-                            }
-
-                            false
-                        })
-                        .unwrap_or(true);
-
-                    if show_this {
-                        ui.allocate_ui_with_layout(
-                            Vec2::new(100f32, 150f32),
-                            Layout::top_down(egui::Align::Min),
-                            |ui| {
-                                ui.group(|ui| {
-                                    let button = ImageButton::new(egui::Image::from_texture(
-                                        ItemsWindow::get_sized_texture(image),
-                                    ));
-                                    let response = ui.add(button).on_hover_text(&item.name);
-
-                                    if response.clicked_by(PointerButton::Primary) {
-                                        self.remove_item_tx.send((item.clone(), 1, true)).unwrap();
-                                    } else if response.clicked_by(PointerButton::Secondary) {
-                                        self.remove_item_tx.send((item.clone(), 1, false)).unwrap();
-                                    } else if response.clicked_by(PointerButton::Middle) {
-                                        self.modal_clicked_item = Some(item.clone());
-                                        self.modal_quantity = Default::default();
-                                        quantity_modal.open();
-                                    }
-
-                                    ui.label(quantity.to_string());
-                                });
-                            },
-                        );
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ComboBox::from_id_salt("wish list modal project")
+                    .selected_text(Self::project_name(projects, self.modal_project_id))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.modal_project_id, None, "No project");
+                        projects.iter().for_each(|project| {
+                            ui.selectable_value(
+                                &mut self.modal_project_id,
+                                Some(project.id),
+                                &project.name,
+                            );
+                        });
+                    });
+
+                if ui.button("Move").clicked() {
+                    if let Some(item) = self.modal_clicked_item.take() {
+                        self.project_action_tx
+                            .send(ProjectAction::MoveItem(item, self.modal_project_id))
+                            .unwrap();
                     }
+                    quantity_modal.close();
                 }
             });
         });
+
+        projects.iter().enumerate().for_each(|(index, project)| {
+            ui.separator();
+            self.project_header_ui(
+                ui,
+                project,
+                projects_progress.get(&project.id).copied().unwrap_or(0f32),
+                index == 0,
+                index + 1 == projects.len(),
+            );
+            self.items_ui(
+                ui,
+                items,
+                items_images,
+                current_sub_areas,
+                &quantity_modal,
+                |item| items_project.get(item) == Some(&project.id),
+            );
+        });
+
+        if !projects.is_empty() {
+            ui.separator();
+            ui.label(RichText::new("No project").strong());
+        }
+
+        self.items_ui(
+            ui,
+            items,
+            items_images,
+            current_sub_areas,
+            &quantity_modal,
+            |item| !items_project.contains_key(item),
+        );
+    }
+
+    fn new_project_ui(&mut self, ui: &mut Ui, projects: &[Project]) {
+        ui.horizontal(|ui| {
+            ui.add(
+                TextEdit::singleline(&mut self.new_project_name)
+                    .hint_text("Project")
+                    .desired_width(150f32),
+            );
+            ui.add(
+                TextEdit::singleline(&mut self.new_project_deadline)
+                    .hint_text("Deadline YYYY-MM-DD")
+                    .desired_width(150f32),
+            );
+
+            let name = self.new_project_name.trim();
+            let deadline = self.new_project_deadline.trim();
+            let parsed_deadline = NaiveDate::parse_from_str(deadline, "%Y-%m-%d").ok();
+            let valid = !name.is_empty()
+                && !projects.iter().any(|project| project.name == name)
+                && (deadline.is_empty() || parsed_deadline.is_some());
+
+            if ui
+                .add_enabled(valid, egui::Button::new("New project"))
+                .clicked()
+            {
+                self.project_action_tx
+                    .send(ProjectAction::Create(name.to_owned(), parsed_deadline))
+                    .unwrap();
+                self.new_project_name.clear();
+                self.new_project_deadline.clear();
+            }
+        });
+    }
+
+    fn project_header_ui(
+        &mut self,
+        ui: &mut Ui,
+        project: &Project,
+        progress: f32,
+        first: bool,
+        last: bool,
+    ) {
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(&project.name).strong());
+
+            if let Some(deadline) = project.deadline {
+                let text = RichText::new(deadline.format("%Y-%m-%d").to_string());
+                if deadline < Local::now().date_naive() && progress < 1f32 {
+                    ui.label(text.color(Color32::RED))
+                        .on_hover_text("Deadline passed");
+                } else {
+                    ui.label(text);
+                }
+            }
+
+            ui.add(
+                ProgressBar::new(progress)
+                    .desired_width(120f32)
+                    .show_percentage(),
+            )
+            .on_hover_text("Base ingredients already in the inventory");
+
+            if ui.add_enabled(!first, egui::Button::new("⏶")).clicked() {
+                self.project_action_tx
+                    .send(ProjectAction::Raise(project.id))
+                    .unwrap();
+            }
+            if ui.add_enabled(!last, egui::Button::new("⏷")).clicked() {
+                self.project_action_tx
+                    .send(ProjectAction::Lower(project.id))
+                    .unwrap();
+            }
+            if ui
+                .button("Delete")
+                .on_hover_text("Its items go back to the main list")
+                .clicked()
+            {
+                self.project_action_tx
+                    .send(ProjectAction::Delete(project.id))
+                    .unwrap();
+            }
+        });
+    }
+
+    fn project_name(projects: &[Project], project_id: Option<i32>) -> &str {
+        projects
+            .iter()
+            .find(|project| Some(project.id) == project_id)
+            .map(|project| project.name.as_str())
+            .unwrap_or("No project")
+    }
+
+    fn items_ui(
+        &mut self,
+        ui: &mut Ui,
+        items: &ItemsRelations,
+        items_images: &HashMap<Item, AsyncStatus<Image>>,
+        current_sub_areas: &HashSet<SubArea>,
+        quantity_modal: &Modal,
+        in_group: impl Fn(&Item) -> bool,
+    ) {
+        ui.horizontal_wrapped(|ui| {
+            items.iter().filter(|(item, _)| in_group(item)).for_each(
+                |(item, (quantity, ingredients))| {
+                    if let Some(AsyncStatus::Ready(image)) = items_images.get(item) {
+                        let show_this = current_sub_areas.is_empty()
+                            || if let AsyncStatus::Ready((ingredients, _steps)) = ingredients {
+                                ingredients.iter().any(|(_, (_, monsters))| {
+                                    monsters.iter().any(|(_, sub_areas)| {
+                                        !sub_areas.is_disjoint(current_sub_areas)
+                                    })
+                                })
+                            } else {
+                                false
+                            };
+
+                        if show_this {
+                            ui.allocate_ui_with_layout(
+                                Vec2::new(100f32, 150f32),
+                                Layout::top_down(egui::Align::Min),
+                                |ui| {
+                                    ui.group(|ui| {
+                                        let button = ImageButton::new(egui::Image::from_texture(
+                                            ItemsWindow::get_sized_texture(image),
+                                        ));
+                                        let response = ui.add(button).on_hover_text(&item.name);
+
+                                        if response.clicked_by(PointerButton::Primary) {
+                                            self.remove_item_tx
+                                                .send((item.clone(), 1, true))
+                                                .unwrap();
+                                        } else if response.clicked_by(PointerButton::Secondary) {
+                                            self.remove_item_tx
+                                                .send((item.clone(), 1, false))
+                                                .unwrap();
+                                        } else if response.clicked_by(PointerButton::Middle) {
+                                            self.modal_clicked_item = Some(item.clone());
+                                            self.modal_quantity = Default::default();
+                                            self.modal_project_id = None;
+                                            quantity_modal.open();
+                                        }
+
+                                        ui.label(quantity.to_string());
+                                    });
+                                },
+                            );
+                        }
+                    }
+                },
+            );
+        });
     }
 }